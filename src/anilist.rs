@@ -0,0 +1,290 @@
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use animeku_cli::t;
+use anyhow::{anyhow, bail};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::config::{self, http_client};
+
+static SEARCH_QUERY: &str = r#"query ($search: String) {
+  Page(perPage: 10) {
+    media(search: $search, type: ANIME) {
+      id
+      episodes
+      seasonYear
+      title { romaji english }
+    }
+  }
+}"#;
+
+static PROGRESS_QUERY: &str = r#"query ($id: Int) {
+  Media(id: $id) {
+    mediaListEntry { progress }
+  }
+}"#;
+
+static SAVE_MUTATION: &str = r#"mutation ($mediaId: Int, $progress: Int, $status: MediaListStatus) {
+  SaveMediaListEntry(mediaId: $mediaId, progress: $progress, status: $status) {
+    id
+    progress
+    status
+  }
+}"#;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Media {
+    pub id: u64,
+    pub title: String,
+    pub episodes: Option<usize>,
+    pub year: Option<u32>,
+}
+
+impl std::fmt::Display for Media {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.title)?;
+        if let Some(year) = self.year {
+            write!(f, " ({})", year)?;
+        }
+        if let Some(episodes) = self.episodes {
+            write!(f, " - {} eps", episodes)?;
+        }
+        Ok(())
+    }
+}
+
+impl Media {
+    fn from_value(value: &Value) -> Option<Self> {
+        let title = &value["title"];
+        Some(Self {
+            id: value["id"].as_u64()?,
+            title: title["english"]
+                .as_str()
+                .or(title["romaji"].as_str())?
+                .to_string(),
+            episodes: value["episodes"].as_u64().map(|n| n as usize),
+            year: value["seasonYear"].as_u64().map(|n| n as u32),
+        })
+    }
+}
+
+pub struct Anilist {
+    client: Client,
+    endpoint: String,
+    token: String,
+}
+
+impl Anilist {
+    /// Returns `None` when no token is configured.
+    pub fn from_config() -> Option<Self> {
+        let anilist = &config::get().anilist;
+        Some(Self {
+            client: http_client(),
+            endpoint: anilist.endpoint.clone(),
+            token: anilist.token.clone().filter(|t| !t.trim().is_empty())?,
+        })
+    }
+
+    async fn query(&self, query: &str, variables: Value) -> anyhow::Result<Value> {
+        let resp: Value = self
+            .client
+            .post(&self.endpoint)
+            .bearer_auth(&self.token)
+            .json(&json!({ "query": query, "variables": variables }))
+            .send()
+            .await?
+            .json()
+            .await?;
+
+        if let Some(error) = resp["errors"].as_array().and_then(|e| e.first()) {
+            let message = error["message"].as_str().unwrap_or_default();
+            bail!(t!("anilist.error", message));
+        }
+        Ok(resp["data"].clone())
+    }
+
+    pub async fn search(&self, title: &str) -> anyhow::Result<Vec<Media>> {
+        let data = self.query(SEARCH_QUERY, json!({ "search": title })).await?;
+        Ok(data["Page"]["media"]
+            .as_array()
+            .map(|media| media.iter().filter_map(Media::from_value).collect())
+            .unwrap_or_default())
+    }
+
+    pub async fn progress(&self, media: &Media) -> anyhow::Result<usize> {
+        let data = self
+            .query(PROGRESS_QUERY, json!({ "id": media.id }))
+            .await?;
+        Ok(data["Media"]["mediaListEntry"]["progress"]
+            .as_u64()
+            .unwrap_or_default() as usize)
+    }
+
+    /// Saves `progress` unless the list already has a higher count, returns
+    /// whether anything was written.
+    pub async fn save_progress(&self, media: &Media, progress: usize) -> anyhow::Result<bool> {
+        if self.progress(media).await? >= progress {
+            return Ok(false);
+        }
+        let status = match media.episodes {
+            Some(total) if progress >= total => "COMPLETED",
+            _ => "CURRENT",
+        };
+        let data = self
+            .query(
+                SAVE_MUTATION,
+                json!({ "mediaId": media.id, "progress": progress, "status": status }),
+            )
+            .await?;
+        data["SaveMediaListEntry"]["id"]
+            .as_u64()
+            .ok_or_else(|| anyhow!(t!("anilist.error", "SaveMediaListEntry")))?;
+        Ok(true)
+    }
+}
+
+/// Confirmed links between a source title and an AniList entry. `None` marks
+/// titles the user chose not to sync.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Mapping {
+    pub titles: HashMap<String, Option<Media>>,
+}
+
+pub fn path() -> PathBuf {
+    config::data_dir().join("anilist.json")
+}
+
+fn key(source: &str, id: &str) -> String {
+    format!("{}:{}", source, id)
+}
+
+impl Mapping {
+    pub fn load() -> Self {
+        fs::read(path())
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        let path = path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn get(&self, source: &str, id: &str) -> Option<&Option<Media>> {
+        self.titles.get(&key(source, id))
+    }
+
+    pub fn set(&mut self, source: &str, id: &str, media: Option<Media>) {
+        self.titles.insert(key(source, id), media);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use axum::{
+        extract::State,
+        http::{HeaderMap, StatusCode},
+        routing::post,
+        Json, Router,
+    };
+
+    use super::*;
+
+    type Requests = Arc<Mutex<Vec<(String, Value)>>>;
+
+    async fn graphql(
+        State(requests): State<Requests>,
+        headers: HeaderMap,
+        Json(body): Json<Value>,
+    ) -> (StatusCode, Json<Value>) {
+        let auth = headers
+            .get("Authorization")
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        let query = body["query"].as_str().unwrap_or_default().to_string();
+        requests.lock().unwrap().push((auth.clone(), body));
+
+        if auth != "Bearer secret" {
+            let errors = json!({
+                "errors": [{ "message": "Invalid token", "status": 400 }],
+                "data": null
+            });
+            return (StatusCode::BAD_REQUEST, Json(errors));
+        }
+        let data = if query.contains("SaveMediaListEntry") {
+            json!({ "SaveMediaListEntry": { "id": 77, "progress": 12, "status": "COMPLETED" } })
+        } else {
+            json!({ "Media": { "mediaListEntry": { "progress": 3 } } })
+        };
+        (StatusCode::OK, Json(json!({ "data": data })))
+    }
+
+    fn mock(token: &str) -> (Anilist, Requests) {
+        let requests = Requests::default();
+        let app = Router::new()
+            .route("/", post(graphql))
+            .with_state(requests.clone());
+        let server = axum::Server::bind(&([127, 0, 0, 1], 0).into()).serve(app.into_make_service());
+        let anilist = Anilist {
+            client: Client::new(),
+            endpoint: format!("http://{}/", server.local_addr()),
+            token: token.into(),
+        };
+        tokio::spawn(server);
+        (anilist, requests)
+    }
+
+    fn media(episodes: Option<usize>) -> Media {
+        Media {
+            id: 21,
+            title: "One Piece".into(),
+            episodes,
+            year: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn saves_media_list_entry() {
+        let (anilist, requests) = mock("secret");
+        assert!(anilist.save_progress(&media(Some(12)), 12).await.unwrap());
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].1["query"], PROGRESS_QUERY);
+        assert_eq!(requests[0].1["variables"], json!({ "id": 21 }));
+
+        let (auth, body) = &requests[1];
+        assert_eq!(auth, "Bearer secret");
+        assert_eq!(body["query"], SAVE_MUTATION);
+        assert_eq!(
+            body["variables"],
+            json!({ "mediaId": 21, "progress": 12, "status": "COMPLETED" })
+        );
+    }
+
+    #[tokio::test]
+    async fn skips_lower_progress() {
+        let (anilist, requests) = mock("secret");
+        assert!(!anilist.save_progress(&media(None), 2).await.unwrap());
+        assert_eq!(requests.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn reports_token_error() {
+        let (anilist, requests) = mock("expired");
+        let error = anilist.save_progress(&media(None), 4).await.unwrap_err();
+        assert!(error.to_string().contains("Invalid token"));
+        assert_eq!(requests.lock().unwrap().len(), 1);
+    }
+}
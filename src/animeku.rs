@@ -1,63 +1,66 @@
-use std::{
-    collections::HashMap,
-    io::{stdout, Write},
-};
+use std::io::{stdout, Write};
 
+use animeku_cli::{
+    ext::SOURCES,
+    i18n::meta_key,
+    models::{Episode, Meta, Movie, Stream},
+    pipeline::{pick_stream, Pipeline},
+    t,
+};
 use colored::Colorize;
+use dialoguer::theme::ColorfulTheme;
 
 use crate::{
-    ext::Ext,
-    input,
-    models::{Episode, Meta, Movie, Stream},
-    util::show_image_thumb,
+    config, input, metadata, thumbnail,
+    watchlist::{Entry, Watchlist},
 };
 
+static FAVORITE_ID: &str = "favorite";
+static FOLLOW_ID: &str = "follow";
+
 pub struct AnimekuCli {
-    movie_cache: HashMap<String, Vec<Movie>>,
-    episode_cache: HashMap<String, (Vec<Episode>, Meta)>,
-    stream_cache: HashMap<String, Vec<Stream>>,
-    extractor: Box<dyn Ext>,
+    source: &'static str,
+    pipeline: Pipeline,
 }
 
 impl AnimekuCli {
-    pub fn new(extractor: Box<dyn Ext>) -> Self {
+    pub fn new(source: usize) -> Self {
         Self {
-            movie_cache: HashMap::new(),
-            episode_cache: HashMap::new(),
-            stream_cache: HashMap::new(),
-            extractor,
+            source: SOURCES[source],
+            pipeline: Pipeline::for_source(source, &config::get().ext()).with_enricher(
+                metadata::enricher(SOURCES[source], |message| {
+                    eprintln!("{} {}", "■".red(), message.yellow())
+                }),
+            ),
         }
     }
 
-    pub async fn search(&mut self, search_title: &str) -> anyhow::Result<Movie> {
+    pub async fn search(&self, search_title: &str) -> anyhow::Result<Movie> {
         print!(
-            "{} Proses pencarian '{}'.. ",
+            "{} {}",
             "◆".blue(),
-            search_title.green()
+            t!("search.progress", search_title.green())
         );
         stdout().flush()?;
 
         let mut page = 1;
         let mut is_latest = false;
+        let mut announced = false;
 
         loop {
-            if let std::collections::hash_map::Entry::Vacant(_) =
-                self.movie_cache.entry(page.to_string())
-            {
-                let (movie_list, total) = self.extractor.search(search_title.into(), page).await?;
-                if page == 1 && !movie_list.is_empty() {
-                    println!("ditemukan {} judul", total.to_string().green());
-                }
-                self.movie_cache.insert(page.to_string(), movie_list);
+            let (mut movie_list, total) = self.pipeline.search(search_title, page).await?;
+            if !announced && !movie_list.is_empty() {
+                println!("{}", t!("search.found", total.to_string().green()));
+                announced = true;
             }
 
-            let mut movie_list = self.movie_cache.get(&page.to_string()).unwrap().clone();
             if movie_list.is_empty() {
                 if page == 1 {
                     eprintln!(
-                        "tidak ditemukan!\n{} {}",
+                        "{}\n{} {}\n",
+                        t!("search.not_found"),
                         "■".red(),
-                        "Silahkan periksa kembali kata kunci pencarian\n".yellow()
+                        t!("search.check_keyword").yellow()
                     );
                     std::process::exit(0);
                 }
@@ -69,16 +72,18 @@ impl AnimekuCli {
             if page > 1 {
                 movie_list.push(Movie {
                     id: "1".into(),
-                    title: format!("Sebelumnnya (Halaman {})", page - 1),
+                    title: t!("search.prev_page", page - 1),
                     total_episodes: None,
+                    meta: Meta::default(),
                 })
             }
 
             if !is_latest {
                 movie_list.push(Movie {
                     id: "2".into(),
-                    title: format!("Selanjutnya (Halaman {})", page + 1),
+                    title: t!("search.next_page", page + 1),
                     total_episodes: None,
+                    meta: Meta::default(),
                 })
             }
 
@@ -87,86 +92,247 @@ impl AnimekuCli {
                 page -= 1;
             } else if movie.id == "2" {
                 page += 1;
-            } else {
+            } else if self.preview(&movie).await? {
                 return Ok(movie);
             }
         }
     }
 
-    pub async fn extract_episode(&mut self, movie: Movie) -> anyhow::Result<Episode> {
+    /// Shows the poster and details of a search result, returns whether to
+    /// open it or go back to the results. The title can be saved to the
+    /// favorites from here.
+    async fn preview(&self, movie: &Movie) -> anyhow::Result<bool> {
+        clearscreen_and_show_banner()?;
+        if movie.meta.thumb_url.is_some() || !movie.meta.data.is_empty() {
+            println!(
+                "  {}{}\n",
+                " ".repeat(45 / 2 - 5),
+                t!("details.title").black().on_truecolor(252, 136, 3)
+            );
+            if let Some(ref thumb_url) = movie.meta.thumb_url {
+                thumbnail::show(thumb_url).await;
+            }
+        }
+        println!("  {}", movie.title.trim().bright_white().bold());
+        print_meta(&movie.meta);
+        println!();
+
+        loop {
+            let saved = Watchlist::load().get(self.source, &movie.id).is_some();
+            let selected = dialoguer::Select::with_theme(&ColorfulTheme::default())
+                .with_prompt(t!("input.choose"))
+                .default(0)
+                .item(t!("preview.open"))
+                .item(if saved {
+                    t!("favorite.remove")
+                } else {
+                    t!("favorite.add")
+                })
+                .item(t!("preview.back"))
+                .interact()?;
+            match selected {
+                0 => return Ok(true),
+                1 => {
+                    let episodes = Watchlist::load()
+                        .get(self.source, &movie.id)
+                        .map_or(0, |e| e.episodes);
+                    self.toggle_favorite(movie, Entry::new(self.source, movie, episodes))?;
+                }
+                _ => {
+                    clearscreen_and_show_banner()?;
+                    return Ok(false);
+                }
+            }
+        }
+    }
+
+    /// Saves `movie` as `entry`, or removes it when it is already saved.
+    fn toggle_favorite(&self, movie: &Movie, entry: Entry) -> anyhow::Result<()> {
+        let mut watchlist = Watchlist::load();
+        if watchlist.get(self.source, &movie.id).is_some() {
+            watchlist.remove(self.source, &movie.id);
+            println!(
+                "{} {}",
+                "◆".blue(),
+                t!("favorite.removed", movie.title.trim())
+            );
+        } else {
+            watchlist.add(entry);
+            println!(
+                "{} {}",
+                "◆".blue(),
+                t!("favorite.added", movie.title.trim())
+            );
+        }
+        watchlist.save()
+    }
+
+    pub fn source(&self) -> &'static str {
+        self.source
+    }
+
+    pub fn pipeline(&self) -> &Pipeline {
+        &self.pipeline
+    }
+
+    pub async fn extract_episode(&self, movie: Movie) -> anyhow::Result<Episode> {
+        let (episodes, meta) = self.show_episodes(movie.clone()).await?;
+
+        loop {
+            let watchlist = Watchlist::load();
+            let saved = watchlist.get(self.source, &movie.id);
+            let followed = saved.is_some_and(|e| e.followed);
+            let saved = saved.is_some();
+
+            let mut options = episodes.clone();
+            options.push(Episode {
+                id: FAVORITE_ID.into(),
+                title: if saved {
+                    t!("favorite.remove")
+                } else {
+                    t!("favorite.add")
+                }
+                .into(),
+                is_series: false,
+            });
+            options.push(Episode {
+                id: FOLLOW_ID.into(),
+                title: if followed {
+                    t!("follow.remove")
+                } else {
+                    t!("follow.add")
+                }
+                .into(),
+                is_series: false,
+            });
+
+            let selected = input::choice(options, true)?;
+            if selected.id != FAVORITE_ID && selected.id != FOLLOW_ID {
+                return Ok(selected);
+            }
+
+            let mut entry = Entry::new(self.source, &movie, episodes.len());
+            entry.thumb_url = meta.thumb_url.clone().or(entry.thumb_url);
+            if selected.id == FAVORITE_ID {
+                self.toggle_favorite(&movie, entry)?;
+                continue;
+            }
+
+            let mut watchlist = Watchlist::load();
+            if followed {
+                watchlist.unfollow(self.source, &movie.id);
+                println!(
+                    "{} {}",
+                    "◆".blue(),
+                    t!("follow.removed", movie.title.trim())
+                );
+            } else {
+                let seen_ids = episodes.iter().map(|e| e.id.clone()).collect();
+                watchlist.follow(entry, seen_ids);
+                println!("{} {}", "◆".blue(), t!("follow.added", movie.title.trim()));
+            }
+            watchlist.save()?;
+        }
+    }
+
+    pub async fn select_episodes(&self, movie: Movie) -> anyhow::Result<Vec<Episode>> {
+        let (episodes, _) = self.show_episodes(movie).await?;
+        input::multi_choice(episodes)
+    }
+
+    async fn show_episodes(&self, movie: Movie) -> anyhow::Result<(Vec<Episode>, Meta)> {
+        let movie_id = movie.id.clone();
         print!(
-            "{} Memuat daftar episode '{}' .. ",
+            "{} {}",
             "◆".blue(),
-            movie.id.green()
+            t!("episode.progress", movie.id.green())
         );
         stdout().flush()?;
 
-        let id = movie.id.clone();
-
-        if !self.episode_cache.contains_key(&id) {
-            let item = self.extractor.get_episodes(movie).await?;
-            self.episode_cache.insert(id.clone(), item);
-        }
-
-        let (episodes, meta) = self.episode_cache.get(&id).unwrap().to_owned();
+        let (episodes, meta) = self.pipeline.episodes(movie).await?;
         if episodes.is_empty() {
-            println!("tidak berhasil!");
+            println!("{}", t!("status.unsuccessful"));
             std::process::exit(0);
         }
 
-        println!("berhasil");
+        println!("{}", t!("status.success"));
         println!(
             "\n  {}{}\n",
             " ".repeat(45 / 2 - 5),
-            " Deskripsi ".black().on_truecolor(252, 136, 3)
+            t!("details.title").black().on_truecolor(252, 136, 3)
         );
 
         if let Some(ref thumb_url) = meta.thumb_url {
-            show_image_thumb(thumb_url.to_string()).await;
+            thumbnail::show(thumb_url).await;
         }
 
-        let mut has_episode = false;
-        for (k, v) in meta.data {
-            if k.to_lowercase().contains("episode") {
-                has_episode = true;
-            }
-            println!("  {} : {}", k.bright_white(), v);
+        let mut watchlist = Watchlist::load();
+        if watchlist.seen(self.source, &movie_id, episodes.len()) {
+            watchlist.save()?;
         }
-        if !has_episode {
-            println!("  {} : {}", "Episodes".bright_white(), episodes.len());
+
+        print_meta(&meta);
+        if !meta
+            .data
+            .iter()
+            .any(|(k, _)| k.to_lowercase().contains("episode"))
+        {
+            println!(
+                "  {} : {}",
+                meta_key("Episode").bright_white(),
+                episodes.len()
+            );
         }
         println!();
-
-        let selected = input::choice(episodes, true)?;
-        Ok(selected)
+        Ok((episodes, meta))
     }
 
-    pub async fn extract_stream_urls(&mut self, episode: Episode) -> anyhow::Result<Stream> {
+    pub async fn extract_stream_urls(&self, episode: Episode) -> anyhow::Result<Stream> {
         print!(
-            "{} Memuat tautan unduhan '{}' .. ",
+            "{} {}",
             "◆".blue(),
-            episode.id.green()
+            t!("stream.progress", episode.id.green())
         );
         stdout().flush()?;
 
-        let id = episode.id.clone();
-        if !self.stream_cache.contains_key(&id) {
-            let streams = self.extractor.get_stream_urls(episode).await?;
-            self.stream_cache.insert(id.clone(), streams);
-        }
-
-        let streams = self.stream_cache.get(&id).unwrap().to_owned();
+        let streams = self.pipeline.streams(episode).await?;
         if streams.is_empty() {
             eprintln!(
-                "gagal!\n{} {}",
+                "{}\n{} {}\n",
+                t!("status.failed_excl"),
                 "■".red(),
-                "Silahkan jalankan ulang program\n".yellow()
+                t!("stream.restart").yellow()
             );
             std::process::exit(0);
         }
-        println!("berhasil");
+        println!("{}", t!("status.success"));
+
+        if let Some(stream) = pick_stream(&streams, config::get().quality.as_deref()) {
+            return Ok(stream.clone());
+        }
 
         let selected = input::choice(streams, false)?;
         Ok(selected)
     }
 }
+
+fn print_meta(meta: &Meta) {
+    for (k, v) in meta.data.iter() {
+        println!("  {} : {}", meta_key(k).bright_white(), v);
+    }
+}
+
+pub fn clearscreen_and_show_banner() -> anyhow::Result<()> {
+    clearscreen::clear()?;
+
+    eprintln!(
+        "{} v{} {} val \n",
+        r#"
+  ▄▀█ █▄░█ █ █▀▄▀█ █▀▀ █▄▀ █░█ ▄▄ █▀▀ █░░ █
+  █▀█ █░▀█ █ █░▀░█ ██▄ █░█ █▄█ ░░ █▄▄ █▄▄ █ "#
+            .bright_green(),
+        env!("CARGO_PKG_VERSION"),
+        "©".cyan()
+    );
+    Ok(())
+}
@@ -0,0 +1,280 @@
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand, ValueEnum};
+
+use crate::config::Config;
+
+#[derive(Debug, Parser)]
+#[command(
+    version,
+    about = "Streaming anime dan film Asia dengan subtitle Indonesia lewat terminal"
+)]
+pub struct Args {
+    /// Judul yang ingin dicari
+    pub title: Option<String>,
+
+    /// Sumber pencarian: anime, movie atau tenflix
+    #[arg(short, long)]
+    pub source: Option<String>,
+
+    /// Aplikasi pemutar video
+    #[arg(short, long)]
+    pub player: Option<String>,
+
+    /// Argumen tambahan untuk pemutar video (bisa diulang)
+    #[arg(long = "player-arg", allow_hyphen_values = true)]
+    pub player_args: Vec<String>,
+
+    /// Putar video lewat proxy lokal agar header dan login ikut terkirim
+    #[arg(long)]
+    pub restream: bool,
+
+    /// Kualitas video yang dipilih otomatis, misalnya 720p
+    #[arg(short, long)]
+    pub quality: Option<String>,
+
+    /// Proxy untuk semua permintaan HTTP
+    #[arg(long)]
+    pub proxy: Option<String>,
+
+    /// Endpoint DNS-over-HTTPS, misalnya https://cloudflare-dns.com/dns-query
+    #[arg(long)]
+    pub doh: Option<String>,
+
+    /// Jangan tampilkan gambar thumbnail
+    #[arg(long)]
+    pub no_thumbnail: bool,
+
+    /// Bahasa tampilan
+    #[arg(long)]
+    pub lang: Option<String>,
+
+    /// Folder unduhan
+    #[arg(long)]
+    pub download_dir: Option<PathBuf>,
+
+    /// Gunakan tampilan layar penuh (TUI)
+    #[arg(long)]
+    pub tui: bool,
+
+    /// Lokasi file konfigurasi
+    #[arg(short, long)]
+    pub config: Option<PathBuf>,
+
+    /// Profil pengguna yang dipakai
+    #[arg(long)]
+    pub profile: Option<String>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Tampilkan atau ubah konfigurasi
+    Config {
+        #[command(subcommand)]
+        action: Option<ConfigAction>,
+    },
+    /// Simpan episode pilihan sebagai playlist
+    Export {
+        /// Judul yang ingin dicari
+        title: Option<String>,
+
+        /// Format playlist
+        #[arg(short, long, value_enum)]
+        format: Option<PlaylistFormat>,
+
+        /// Lokasi file playlist
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Jumlah tautan yang dicari bersamaan
+        #[arg(short, long, default_value_t = 8)]
+        jobs: usize,
+    },
+    /// Periksa episode baru dari judul yang diikuti
+    Check {
+        /// Terus berjalan dan periksa secara berkala
+        #[arg(short, long)]
+        daemon: bool,
+
+        /// Jeda antar pemeriksaan dalam menit
+        #[arg(short, long)]
+        interval: Option<u64>,
+
+        /// Unduh episode baru ke folder pustaka
+        #[arg(long)]
+        download: bool,
+    },
+    /// Buat feed Atom dari episode baru yang ditemukan oleh `check`
+    Feed {
+        /// Lokasi file feed, kosongkan untuk menulis ke layar
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Alamat server yang dipakai pada tautan episode
+        #[arg(long)]
+        base_url: Option<String>,
+    },
+    /// Simpan perkiraan jadwal episode berikutnya sebagai kalender .ics
+    Calendar {
+        /// Lokasi file kalender
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Ekspor atau impor riwayat tontonan dan favorit
+    History {
+        #[command(subcommand)]
+        action: HistoryAction,
+    },
+    /// Sinkronkan riwayat, favorit dan posisi tontonan ke WebDAV atau folder
+    Sync,
+    /// Kelola profil pengguna
+    Profile {
+        #[command(subcommand)]
+        action: ProfileAction,
+    },
+    /// Jalankan server REST API
+    Serve {
+        /// Alamat yang didengarkan
+        #[arg(long)]
+        host: Option<String>,
+
+        /// Port yang didengarkan
+        #[arg(long)]
+        port: Option<u16>,
+
+        /// Token yang wajib dikirim klien
+        #[arg(long)]
+        token: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum PlaylistFormat {
+    M3u8,
+    Xspf,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ListFormat {
+    Mal,
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum HistoryAction {
+    /// Simpan riwayat dan favorit ke file
+    Export {
+        /// Format file: XML MyAnimeList, JSON atau CSV
+        #[arg(short, long, value_enum, default_value_t = ListFormat::Mal)]
+        format: ListFormat,
+
+        /// Lokasi file
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Impor daftar anime dari file ekspor MyAnimeList (XML)
+    Import {
+        file: PathBuf,
+
+        /// Jumlah judul yang dicari bersamaan
+        #[arg(short, long, default_value_t = 4)]
+        jobs: usize,
+    },
+    /// Cocokkan judul hasil impor yang belum ditemukan
+    Review,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ProfileAction {
+    /// Tampilkan semua profil
+    List,
+    /// Buat profil baru
+    Add {
+        name: String,
+
+        /// Kunci profil dengan PIN
+        #[arg(long)]
+        pin: bool,
+    },
+    /// Ubah atau hapus PIN profil (kosongkan untuk menghapus)
+    Pin { name: String },
+    /// Hapus profil beserta datanya
+    Remove { name: String },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ConfigAction {
+    /// Tampilkan seluruh konfigurasi
+    Show,
+    /// Tampilkan lokasi file konfigurasi
+    Path,
+    /// Tampilkan nilai sebuah kunci, misalnya player.command
+    Get { key: String },
+    /// Ubah nilai sebuah kunci; PIN untuk filter.pin ditanyakan langsung
+    Set { key: String, value: Option<String> },
+    /// Hapus nilai sebuah kunci
+    Unset { key: String },
+    /// Buka file konfigurasi di $EDITOR
+    Edit,
+}
+
+impl Args {
+    pub fn apply(&self, config: &mut Config) {
+        if let Some(ref source) = self.source {
+            config.source = Some(source.clone());
+        }
+        if let Some(ref player) = self.player {
+            config.player.command = player.clone();
+        }
+        if !self.player_args.is_empty() {
+            config.player.args = self.player_args.clone();
+        }
+        if self.restream {
+            config.player.restream = true;
+        }
+        if let Some(ref quality) = self.quality {
+            config.quality = Some(quality.clone());
+        }
+        if let Some(ref proxy) = self.proxy {
+            config.network.proxy = Some(proxy.clone());
+        }
+        if let Some(ref doh) = self.doh {
+            config.network.doh = Some(doh.clone());
+        }
+        if self.no_thumbnail {
+            config.thumbnail.enabled = false;
+        }
+        if let Some(ref lang) = self.lang {
+            config.language = Some(lang.clone());
+        }
+        if let Some(ref dir) = self.download_dir {
+            config.download_dir = Some(dir.clone());
+        }
+        if self.tui {
+            config.tui = true;
+        }
+        if let Some(Command::Check { download: true, .. }) = self.command {
+            config.follow.download = true;
+        }
+        if let Some(Command::Serve {
+            ref host,
+            port,
+            ref token,
+        }) = self.command
+        {
+            if let Some(host) = host {
+                config.server.host = host.clone();
+            }
+            if let Some(port) = port {
+                config.server.port = port;
+            }
+            if let Some(token) = token {
+                config.server.token = Some(token.clone());
+            }
+        }
+    }
+}
@@ -0,0 +1,200 @@
+use animeku_cli::t;
+use chrono::{DateTime, Datelike, Duration, Local, NaiveTime, TimeZone, Timelike, Utc, Weekday};
+
+use crate::watchlist::Entry;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Slot {
+    pub weekday: Weekday,
+    pub time: NaiveTime,
+}
+
+/// Infers the weekly release slot from the times new episodes were first seen.
+/// The most common weekday wins, the time is the median time seen on that day.
+pub fn slot(releases: &[u64]) -> Option<Slot> {
+    let seen = releases
+        .iter()
+        .filter_map(|secs| Local.timestamp_opt(*secs as i64, 0).single())
+        .collect::<Vec<_>>();
+
+    let mut counts = [0usize; 7];
+    for time in &seen {
+        counts[time.weekday().num_days_from_monday() as usize] += 1;
+    }
+    let (day, count) = counts
+        .iter()
+        .enumerate()
+        .max_by_key(|(day, count)| (**count, usize::MAX - day))?;
+    if *count == 0 {
+        return None;
+    }
+    let weekday = Weekday::try_from(day as u8).ok()?;
+
+    let mut times = seen
+        .iter()
+        .filter(|t| t.weekday() == weekday)
+        .map(|t| t.num_seconds_from_midnight())
+        .collect::<Vec<_>>();
+    times.sort_unstable();
+    let median = times[times.len() / 2];
+    let time = NaiveTime::from_num_seconds_from_midnight_opt(median / 60 * 60, 0)?;
+    Some(Slot { weekday, time })
+}
+
+/// Next occurrence of `slot` after `after`.
+pub fn next(slot: Slot, after: DateTime<Local>) -> Option<DateTime<Local>> {
+    let days = (7 + slot.weekday.num_days_from_monday() as i64
+        - after.weekday().num_days_from_monday() as i64)
+        % 7;
+    let date = after.date_naive() + Duration::days(days);
+    let mut next = Local
+        .from_local_datetime(&date.and_time(slot.time))
+        .earliest()?;
+    if next <= after {
+        next += Duration::days(7);
+    }
+    Some(next)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+fn fold(line: &str) -> String {
+    let mut out = String::new();
+    let mut len = 0;
+    for c in line.chars() {
+        if len + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            len = 1;
+        }
+        out.push(c);
+        len += c.len_utf8();
+    }
+    out.push_str("\r\n");
+    out
+}
+
+fn stamp(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+pub fn ics(entries: &[Entry]) -> String {
+    let now = Local::now();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".into(),
+        format!(
+            "PRODID:-//{}//{}//ID",
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_VERSION")
+        ),
+        "CALSCALE:GREGORIAN".into(),
+        format!("X-WR-CALNAME:{}", escape(t!("calendar.name"))),
+    ];
+
+    for entry in entries {
+        let Some(start) = slot(&entry.releases).and_then(|slot| next(slot, now)) else {
+            continue;
+        };
+        let uid = format!("{}-{}", entry.source, entry.id)
+            .replace(|c: char| !c.is_ascii_alphanumeric() && c != '-', "-");
+        lines.extend([
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:{}@{}", uid, env!("CARGO_PKG_NAME")),
+            format!("DTSTAMP:{}", stamp(now.with_timezone(&Utc))),
+            format!("DTSTART:{}", stamp(start.with_timezone(&Utc))),
+            "DURATION:PT30M".into(),
+            "RRULE:FREQ=WEEKLY".into(),
+            format!("SUMMARY:{}", escape(entry.title.trim())),
+            format!(
+                "DESCRIPTION:{}",
+                escape(&t!(
+                    "calendar.description",
+                    entry.episodes + 1,
+                    entry.source
+                ))
+            ),
+            "END:VEVENT".into(),
+        ]);
+    }
+    lines.push("END:VCALENDAR".into());
+    lines.iter().map(|line| fold(line)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        // 1 January 2024 is a Monday.
+        Local
+            .with_ymd_and_hms(2024, 1, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    fn unfold(text: &str) -> String {
+        text.replace("\r\n ", "")
+    }
+
+    #[test]
+    fn slot_takes_busiest_weekday_and_median_time() {
+        let releases =
+            [at(1, 20, 0), at(8, 21, 0), at(15, 20, 10), at(2, 9, 0)].map(|t| t.timestamp() as u64);
+        let slot = slot(&releases).unwrap();
+        assert_eq!(slot.weekday, Weekday::Mon);
+        assert_eq!(slot.time, NaiveTime::from_hms_opt(20, 10, 0).unwrap());
+        assert_eq!(super::slot(&[]), None);
+    }
+
+    #[test]
+    fn next_is_strictly_after() {
+        let slot = Slot {
+            weekday: Weekday::Mon,
+            time: NaiveTime::from_hms_opt(20, 0, 0).unwrap(),
+        };
+        assert_eq!(next(slot, at(8, 19, 0)), Some(at(8, 20, 0)));
+        assert_eq!(next(slot, at(8, 20, 0)), Some(at(15, 20, 0)));
+        assert_eq!(next(slot, at(10, 12, 0)), Some(at(15, 20, 0)));
+    }
+
+    #[test]
+    fn fold_keeps_lines_short_and_characters_whole() {
+        let line = format!("SUMMARY:{}", "é".repeat(60));
+        let folded = fold(&line);
+        assert!(folded.ends_with("\r\n"));
+        for physical in folded.trim_end().split("\r\n") {
+            assert!(physical.len() <= 75);
+        }
+        assert_eq!(unfold(&folded).trim_end(), line);
+    }
+
+    #[test]
+    fn ics_repeats_weekly_from_next_slot() {
+        let releases = vec![at(1, 20, 0).timestamp() as u64];
+        let entry = Entry {
+            source: "anime".into(),
+            id: "one piece".into(),
+            title: "One Piece, Part 1".into(),
+            releases,
+            ..Default::default()
+        };
+        let ics = unfold(&ics(&[entry, Entry::default()]));
+        let start = next(
+            slot(&[at(1, 20, 0).timestamp() as u64]).unwrap(),
+            Local::now(),
+        )
+        .unwrap()
+        .with_timezone(&Utc);
+
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 1);
+        assert!(ics.contains(&format!("DTSTART:{}\r\n", stamp(start))));
+        assert!(ics.contains("RRULE:FREQ=WEEKLY\r\n"));
+        assert!(ics.contains("UID:anime-one-piece@"));
+        assert!(ics.contains("SUMMARY:One Piece\\, Part 1\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+    }
+}
@@ -0,0 +1,396 @@
+use std::{
+    io::{stdout, Write},
+    net::IpAddr,
+    time::Duration,
+};
+
+use animeku_cli::{models::Stream, regex, t};
+use anyhow::{anyhow, bail};
+use colored::Colorize;
+use dialoguer::{theme::ColorfulTheme, Select};
+use html_escape::{decode_html_entities, encode_text};
+use reqwest::{Client, Url};
+use tokio::{net::UdpSocket, time::Instant};
+
+use crate::{
+    config::{self, http_client},
+    server::proxy,
+};
+
+static SSDP_ADDR: &str = "239.255.255.250:1900";
+static AV_TRANSPORT: &str = "urn:schemas-upnp-org:service:AVTransport:1";
+
+#[derive(Debug, Clone)]
+pub struct Renderer {
+    pub name: String,
+    control_url: Url,
+}
+
+impl std::fmt::Display for Renderer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let host = self.control_url.host_str().unwrap_or_default();
+        write!(f, "{} ({})", self.name.trim(), host)
+    }
+}
+
+impl Renderer {
+    pub async fn describe(client: &Client, location: &str) -> anyhow::Result<Self> {
+        let body = client.get(location).send().await?.text().await?;
+        let name = regex!(r"<friendlyName>([^<]*)</friendlyName>")
+            .captures(&body)
+            .map(|cap| decode_html_entities(&cap[1]).to_string())
+            .unwrap_or_else(|| location.to_string());
+
+        let base = match regex!(r"<URLBase>([^<]+)</URLBase>").captures(&body) {
+            Some(cap) => Url::parse(cap[1].trim())?,
+            None => Url::parse(location)?,
+        };
+        let control = regex!(r"(?s)<service>(.*?)</service>")
+            .captures_iter(&body)
+            .map(|cap| cap[1].to_string())
+            .find(|service| service.contains(AV_TRANSPORT))
+            .and_then(|service| {
+                regex!(r"<controlURL>([^<]+)</controlURL>")
+                    .captures(&service)
+                    .map(|cap| cap[1].trim().to_string())
+            })
+            .ok_or_else(|| anyhow!(t!("cast.no_transport", name)))?;
+
+        Ok(Self {
+            name,
+            control_url: base.join(&control)?,
+        })
+    }
+
+    async fn soap(&self, action: &str, args: &[(&str, &str)]) -> anyhow::Result<String> {
+        let args = args
+            .iter()
+            .map(|(key, value)| format!("<{key}>{}</{key}>", encode_text(value)))
+            .collect::<String>();
+        let envelope = format!(
+            concat!(
+                r#"<?xml version="1.0" encoding="utf-8"?>"#,
+                r#"<s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/" "#,
+                r#"s:encodingStyle="http://schemas.xmlsoap.org/soap/encoding/">"#,
+                r#"<s:Body><u:{action} xmlns:u="{service}"><InstanceID>0</InstanceID>{args}</u:{action}>"#,
+                r#"</s:Body></s:Envelope>"#
+            ),
+            action = action,
+            service = AV_TRANSPORT,
+            args = args
+        );
+
+        let resp = http_client()
+            .post(self.control_url.clone())
+            .header("Content-Type", r#"text/xml; charset="utf-8""#)
+            .header("SOAPAction", format!(r#""{}#{}""#, AV_TRANSPORT, action))
+            .body(envelope)
+            .send()
+            .await?;
+        let status = resp.status();
+        let body = resp.text().await?;
+        if !status.is_success() {
+            let reason = regex!(r"<errorDescription>([^<]*)</errorDescription>")
+                .captures(&body)
+                .map(|cap| cap[1].to_string())
+                .unwrap_or_else(|| status.to_string());
+            bail!(t!("cast.failed", action, reason));
+        }
+        Ok(body)
+    }
+
+    pub async fn load(&self, url: &str, title: &str) -> anyhow::Result<()> {
+        let mime = if url.contains(".m3u8") {
+            "application/vnd.apple.mpegurl"
+        } else {
+            "video/mp4"
+        };
+        let metadata = format!(
+            concat!(
+                r#"<DIDL-Lite xmlns="urn:schemas-upnp-org:metadata-1-0/DIDL-Lite/" "#,
+                r#"xmlns:dc="http://purl.org/dc/elements/1.1/" "#,
+                r#"xmlns:upnp="urn:schemas-upnp-org:metadata-1-0/upnp/">"#,
+                r#"<item id="0" parentID="-1" restricted="1"><dc:title>{}</dc:title>"#,
+                r#"<upnp:class>object.item.videoItem</upnp:class>"#,
+                r#"<res protocolInfo="http-get:*:{}:*">{}</res></item></DIDL-Lite>"#
+            ),
+            encode_text(title),
+            mime,
+            encode_text(url)
+        );
+        self.soap(
+            "SetAVTransportURI",
+            &[("CurrentURI", url), ("CurrentURIMetaData", &metadata)],
+        )
+        .await?;
+        Ok(())
+    }
+
+    pub async fn play(&self) -> anyhow::Result<()> {
+        self.soap("Play", &[("Speed", "1")]).await?;
+        Ok(())
+    }
+
+    pub async fn pause(&self) -> anyhow::Result<()> {
+        self.soap("Pause", &[]).await?;
+        Ok(())
+    }
+
+    pub async fn stop(&self) -> anyhow::Result<()> {
+        self.soap("Stop", &[]).await?;
+        Ok(())
+    }
+
+    pub async fn seek(&self, target: &str) -> anyhow::Result<()> {
+        self.soap("Seek", &[("Unit", "REL_TIME"), ("Target", target)])
+            .await?;
+        Ok(())
+    }
+
+    async fn peer_ip(&self) -> anyhow::Result<IpAddr> {
+        let host = self.control_url.host_str().unwrap_or_default();
+        let port = self.control_url.port_or_known_default().unwrap_or(80);
+        tokio::net::lookup_host((host, port))
+            .await?
+            .next()
+            .map(|addr| addr.ip())
+            .ok_or_else(|| anyhow!(t!("cast.not_found")))
+    }
+}
+
+async fn ssdp_search(target: &str, wait: Duration) -> anyhow::Result<Vec<String>> {
+    let socket = UdpSocket::bind("0.0.0.0:0").await?;
+    let request = format!(
+        "M-SEARCH * HTTP/1.1\r\nHOST: {}\r\nMAN: \"ssdp:discover\"\r\nMX: {}\r\nST: urn:schemas-upnp-org:device:MediaRenderer:1\r\n\r\n",
+        SSDP_ADDR,
+        wait.as_secs().max(1)
+    );
+    socket.send_to(request.as_bytes(), target).await?;
+
+    let deadline = Instant::now() + wait;
+    let mut locations = Vec::new();
+    let mut buf = [0u8; 2048];
+    let location_re = regex!(r"(?im)^location:\s*(\S+)");
+    while let Ok(Ok((n, _))) = tokio::time::timeout_at(deadline, socket.recv_from(&mut buf)).await {
+        let response = String::from_utf8_lossy(&buf[..n]);
+        if let Some(cap) = location_re.captures(&response) {
+            let location = cap[1].to_string();
+            if !locations.contains(&location) {
+                locations.push(location);
+            }
+        }
+    }
+    Ok(locations)
+}
+
+pub async fn discover() -> Vec<Renderer> {
+    let cast = &config::get().cast;
+    let mut locations = cast.renderers.clone();
+    for location in ssdp_search(SSDP_ADDR, Duration::from_secs(cast.timeout))
+        .await
+        .unwrap_or_default()
+    {
+        if !locations.contains(&location) {
+            locations.push(location);
+        }
+    }
+
+    let client = http_client();
+    let mut renderers = Vec::new();
+    for location in locations {
+        if let Ok(renderer) = Renderer::describe(&client, &location).await {
+            renderers.push(renderer);
+        }
+    }
+    renderers
+}
+
+fn parse_time(input: &str) -> Option<String> {
+    let parts = input
+        .trim()
+        .split(':')
+        .map(|p| p.parse::<u64>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    if parts.is_empty() || parts.len() > 3 {
+        return None;
+    }
+    let seconds = parts.iter().fold(0, |acc, p| acc * 60 + p);
+    Some(format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    ))
+}
+
+pub async fn run(stream: &Stream) -> anyhow::Result<()> {
+    print!("{} {}", "◆".blue(), t!("cast.searching"));
+    stdout().flush()?;
+    let renderers = discover().await;
+    if renderers.is_empty() {
+        println!("{}", t!("status.failed"));
+        bail!(t!("cast.not_found"));
+    }
+    println!("{}", t!("status.success"));
+
+    let selected = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt(t!("cast.choose"))
+        .default(0)
+        .max_length(5)
+        .items(&renderers)
+        .interact()?;
+    let renderer = &renderers[selected];
+    let needs_proxy =
+        !stream.headers.is_empty() || stream.url.contains('@') || config::get().player.restream;
+    let url = if needs_proxy {
+        proxy::lan_url(stream, renderer.peer_ip().await?).await?
+    } else {
+        stream.url.clone()
+    };
+
+    renderer.load(&url, &stream.title).await?;
+    renderer.play().await?;
+    println!(
+        "{} {}",
+        "◆".blue(),
+        t!("cast.playing", renderer.name.green())
+    );
+
+    let mut paused = false;
+    loop {
+        let pause = if paused {
+            t!("cast.resume")
+        } else {
+            t!("cast.pause")
+        };
+        let action = dialoguer::Select::with_theme(&ColorfulTheme::default())
+            .with_prompt(t!("input.choose"))
+            .default(0)
+            .items(&[pause, t!("cast.seek"), t!("cast.stop")])
+            .interact()?;
+
+        let result = match action {
+            0 if paused => renderer.play().await,
+            0 => renderer.pause().await,
+            1 => {
+                let input: String = dialoguer::Input::with_theme(&ColorfulTheme::default())
+                    .with_prompt(t!("cast.seek_prompt"))
+                    .interact_text()?;
+                match parse_time(&input) {
+                    Some(target) => renderer.seek(&target).await,
+                    None => Err(anyhow!(t!("cast.invalid_time"))),
+                }
+            }
+            _ => return renderer.stop().await,
+        };
+
+        match result {
+            Ok(()) if action == 0 => paused = !paused,
+            Ok(()) => {}
+            Err(e) => eprintln!("{} {}", "■".red(), format!("{:#}", e).yellow()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use axum::{
+        extract::State,
+        http::HeaderMap,
+        routing::{get, post},
+        Router,
+    };
+
+    use super::*;
+
+    type Requests = Arc<Mutex<Vec<(String, String)>>>;
+
+    async fn control(State(requests): State<Requests>, headers: HeaderMap, body: String) {
+        let action = headers
+            .get("SOAPAction")
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        requests.lock().unwrap().push((action, body));
+    }
+
+    /// Serves a device description and records the SOAP calls it receives.
+    fn renderer_stub() -> (String, Requests) {
+        let requests = Requests::default();
+        let app = Router::new()
+            .route(
+                "/device.xml",
+                get(|| async {
+                    concat!(
+                        "<root><device><friendlyName>Living Room TV</friendlyName>",
+                        "<serviceList><service>",
+                        "<serviceType>urn:schemas-upnp-org:service:RenderingControl:1</serviceType>",
+                        "<controlURL>/rendering</controlURL></service><service>",
+                        "<serviceType>urn:schemas-upnp-org:service:AVTransport:1</serviceType>",
+                        "<controlURL>/control</controlURL></service></serviceList></device></root>"
+                    )
+                }),
+            )
+            .route("/control", post(control))
+            .with_state(requests.clone());
+        let server = axum::Server::bind(&([127, 0, 0, 1], 0).into()).serve(app.into_make_service());
+        let location = format!("http://{}/device.xml", server.local_addr());
+        tokio::spawn(server);
+        (location, requests)
+    }
+
+    /// Answers a single M-SEARCH with the given location.
+    async fn ssdp_stub(location: String) -> String {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let addr = socket.local_addr().unwrap().to_string();
+        tokio::spawn(async move {
+            let mut buf = [0u8; 1024];
+            let (n, peer) = socket.recv_from(&mut buf).await.unwrap();
+            let request = String::from_utf8_lossy(&buf[..n]);
+            assert!(request.starts_with("M-SEARCH * HTTP/1.1"));
+            assert!(request.contains("MAN: \"ssdp:discover\""));
+            let response = format!("HTTP/1.1 200 OK\r\nLOCATION: {}\r\n\r\n", location);
+            socket.send_to(response.as_bytes(), peer).await.unwrap();
+        });
+        addr
+    }
+
+    #[tokio::test]
+    async fn sends_transport_envelopes() {
+        config::init(config::Config::default());
+        let (location, requests) = renderer_stub();
+        let target = ssdp_stub(location.clone()).await;
+
+        let locations = ssdp_search(&target, Duration::from_secs(1)).await.unwrap();
+        assert_eq!(locations, vec![location.clone()]);
+
+        let renderer = Renderer::describe(&http_client(), &location).await.unwrap();
+        assert_eq!(renderer.name, "Living Room TV");
+        assert_eq!(renderer.control_url.path(), "/control");
+
+        renderer
+            .load("http://example.com/a.m3u8?x=1&y=2", "Episode <1>")
+            .await
+            .unwrap();
+        renderer.play().await.unwrap();
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+
+        let (action, body) = &requests[0];
+        assert_eq!(action, &format!("\"{}#SetAVTransportURI\"", AV_TRANSPORT));
+        assert!(body.contains(&format!(
+            "<u:SetAVTransportURI xmlns:u=\"{}\"><InstanceID>0</InstanceID>",
+            AV_TRANSPORT
+        )));
+        assert!(body.contains("<CurrentURI>http://example.com/a.m3u8?x=1&amp;y=2</CurrentURI>"));
+        assert!(body.contains("&lt;dc:title&gt;Episode &amp;lt;1&amp;gt;&lt;/dc:title&gt;"));
+        assert!(body.contains("http-get:*:application/vnd.apple.mpegurl:*"));
+
+        let (action, body) = &requests[1];
+        assert_eq!(action, &format!("\"{}#Play\"", AV_TRANSPORT));
+        assert!(body.contains("<InstanceID>0</InstanceID><Speed>1</Speed></u:Play>"));
+    }
+}
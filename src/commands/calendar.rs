@@ -0,0 +1,34 @@
+use std::{fs, path::PathBuf};
+
+use animeku_cli::t;
+use colored::Colorize;
+
+use crate::{calendar, config, watchlist::Watchlist};
+
+pub fn run(output: Option<PathBuf>) -> anyhow::Result<()> {
+    let entries = Watchlist::load()
+        .entries
+        .into_iter()
+        .filter(|e| e.followed && e.scheduled() && !e.releases.is_empty())
+        .collect::<Vec<_>>();
+    let body = calendar::ics(&entries);
+
+    let path = output.unwrap_or_else(|| {
+        config::get()
+            .download_dir
+            .clone()
+            .unwrap_or_default()
+            .join("animeku.ics")
+    });
+    fs::write(&path, body)?;
+    println!(
+        "{} {}",
+        "◆".blue(),
+        t!(
+            "calendar.saved",
+            path.display().to_string().green(),
+            entries.len()
+        )
+    );
+    Ok(())
+}
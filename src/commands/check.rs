@@ -0,0 +1,158 @@
+use std::{
+    collections::HashMap,
+    io::{stdout, Write},
+    process::Command,
+    time::Duration,
+};
+
+use animeku_cli::{ext::source_index, models::Episode, pipeline::Pipeline, t};
+use colored::Colorize;
+
+use crate::{
+    config,
+    download::{self, file_name, Pending, Queue},
+    follow::{self, Check},
+    watchlist::{Entry, Watchlist},
+};
+
+pub async fn run(daemon: bool, interval: Option<u64>) -> anyhow::Result<()> {
+    let interval = interval.unwrap_or(config::get().follow.interval).max(1);
+    loop {
+        if let Err(e) = check().await {
+            eprintln!("{} {}", "■".red(), format!("{:#}", e).yellow());
+        }
+        if !daemon {
+            return Ok(());
+        }
+        println!("{} {}\n", "◆".blue(), t!("check.next", interval));
+        tokio::time::sleep(Duration::from_secs(interval * 60)).await;
+    }
+}
+
+async fn check() -> anyhow::Result<()> {
+    let followed = Watchlist::load()
+        .entries
+        .iter()
+        .filter(|e| e.followed)
+        .count();
+    if followed == 0 {
+        println!("{} {}", "◆".blue(), t!("check.empty"));
+        return Ok(());
+    }
+
+    print!(
+        "{} {}",
+        "◆".blue(),
+        t!("check.progress", followed.to_string().green())
+    );
+    stdout().flush()?;
+    let checks = follow::check(0).await?;
+    println!("{}", t!("status.success"));
+
+    let mut found = false;
+    for Check { entry, result } in checks {
+        match result {
+            Ok(episodes) if episodes.is_empty() => {}
+            Ok(episodes) => {
+                found = true;
+                println!(
+                    "{} {}",
+                    "◆".blue(),
+                    t!("check.new", entry.title.green(), episodes.len())
+                );
+                for episode in &episodes {
+                    println!("  - {}", episode.title.trim());
+                }
+                notify(&entry, &episodes);
+            }
+            Err(e) => eprintln!(
+                "{} {}",
+                "■".red(),
+                t!("check.failed", entry.title, format!("{:#}", e)).yellow()
+            ),
+        }
+    }
+    if !found {
+        println!("{} {}", "◆".blue(), t!("check.none"));
+    }
+    download_pending().await;
+    Ok(())
+}
+
+fn notify(entry: &Entry, episodes: &[Episode]) {
+    if !config::get().follow.notify {
+        return;
+    }
+    let body = episodes
+        .iter()
+        .map(|e| e.title.trim())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let _ = Command::new("notify-send")
+        .args(["--app-name", env!("CARGO_PKG_NAME")])
+        .arg(t!("check.notify_title", entry.title))
+        .arg(body)
+        .status();
+}
+
+/// Downloads queued episodes, keeping failed ones queued for the next check.
+async fn download_pending() {
+    let mut queue = Queue::load();
+    if queue.pending.is_empty() {
+        return;
+    }
+    let follow = &config::get().follow;
+    let library = follow
+        .library
+        .clone()
+        .or_else(|| config::get().download_dir.clone())
+        .unwrap_or_default();
+    let ext = config::get().ext();
+    let mut pipelines = HashMap::new();
+
+    for Pending {
+        source,
+        title,
+        episode,
+    } in queue.pending.clone()
+    {
+        print!(
+            "{} {}",
+            "◆".blue(),
+            t!("check.downloading", episode.title.trim().green())
+        );
+        let _ = stdout().flush();
+
+        let result = async {
+            if !pipelines.contains_key(&source) {
+                let pipeline = Pipeline::for_source(source_index(&source)?, &ext);
+                pipelines.insert(source.clone(), pipeline);
+            }
+            let stream = pipelines[&source]
+                .resolve(episode.clone(), config::get().quality.as_deref())
+                .await?
+                .ok_or_else(|| anyhow::anyhow!(t!("export.no_stream")))?;
+            let path = library.join(file_name(&title)).join(format!(
+                "{}.{}",
+                file_name(&episode.title),
+                download::extension(&stream)
+            ));
+            download::download(&stream, &path).await
+        }
+        .await;
+
+        match result {
+            Ok(()) => {
+                println!("{}", t!("status.success"));
+                queue.remove(&source, &episode.id);
+                if let Err(e) = queue.save() {
+                    eprintln!("{} {}", "■".red(), format!("{:#}", e).yellow());
+                }
+            }
+            Err(e) => {
+                println!("{}", t!("status.failed"));
+                eprintln!("{} {}", "■".red(), format!("{:#}", e).yellow());
+            }
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use std::{env, fs, process::Command};
+
+use animeku_cli::t;
+use anyhow::bail;
+use colored::Colorize;
+
+use crate::{args::ConfigAction, commands::profile, config, profile::hash_pin};
+
+/// Domain of the content filter PIN, see `profile_domain`.
+static FILTER_DOMAIN: &str = "content-filter";
+
+fn filter_key(key: &str) -> bool {
+    key == "filter" || key.starts_with("filter.")
+}
+
+/// The PIN hash stays out of `show` and `get`.
+fn hide_pin(mut config: config::Config) -> config::Config {
+    config.filter.pin = None;
+    config
+}
+
+/// A mandatory filter without a PIN could be turned off by anyone.
+fn check_filter(config: &config::Config) -> anyhow::Result<()> {
+    if config.filter.mandatory && config.filter.pin.is_none() {
+        bail!(t!("filter.pin_first"));
+    }
+    Ok(())
+}
+
+pub fn run(action: Option<ConfigAction>) -> anyhow::Result<()> {
+    let current = config::read()?;
+
+    let action = action.unwrap_or(ConfigAction::Show);
+    let locked = match &action {
+        ConfigAction::Set { key, .. } | ConfigAction::Unset { key } => filter_key(key),
+        ConfigAction::Edit => true,
+        _ => false,
+    };
+    if let (true, Some(hash)) = (locked, &current.filter.pin) {
+        profile::verify(t!("filter.pin"), t!("filter.label"), |pin| {
+            hash_pin(FILTER_DOMAIN, pin) == *hash
+        })?;
+    }
+
+    match action {
+        ConfigAction::Show => {
+            println!(
+                "{} {}\n",
+                "◆".blue(),
+                config::config_path().display().to_string().green()
+            );
+            println!("{}", toml::to_string_pretty(&hide_pin(current))?);
+        }
+        ConfigAction::Path => println!("{}", config::config_path().display()),
+        ConfigAction::Get { key } => {
+            let value = config::get_value(&hide_pin(current), &key)?;
+            match value.as_str() {
+                Some(s) => println!("{}", s),
+                None => println!("{}", value),
+            }
+        }
+        ConfigAction::Set { key, value } if key == "filter.pin" => {
+            if value.is_some() {
+                bail!(t!("filter.pin_argument"));
+            }
+            let pin = profile::ask_pin(t!("filter.new_pin"), true)?;
+            let mut updated = current;
+            updated.filter.pin = Some(hash_pin(FILTER_DOMAIN, &pin));
+            config::write(&updated)?;
+            println!("{} {}", "◆".blue(), t!("filter.pin_saved"));
+        }
+        ConfigAction::Set { key, value } => {
+            let Some(value) = value else {
+                bail!(t!("config.missing_value", key));
+            };
+            let updated = config::set_value(&current, &key, &value)?;
+            check_filter(&updated)?;
+            config::write(&updated)?;
+            println!("{} {} = {}", "◆".blue(), key.bright_white(), value.green());
+        }
+        ConfigAction::Unset { key } => {
+            let updated = config::unset_value(&current, &key)?;
+            check_filter(&updated)?;
+            config::write(&updated)?;
+            println!(
+                "{} {}",
+                "◆".blue(),
+                t!("config.removed", key.bright_white())
+            );
+        }
+        ConfigAction::Edit => {
+            let path = config::config_path();
+            if !path.exists() {
+                config::write(&current)?;
+            }
+            // Edit a copy and only replace the config once it is valid.
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            let draft = path.with_file_name(format!(".{}.edit", name));
+            fs::copy(&path, &draft)?;
+            let editor = env::var("VISUAL")
+                .or_else(|_| env::var("EDITOR"))
+                .unwrap_or_else(|_| "nano".into());
+            let checked = Command::new(editor)
+                .arg(&draft)
+                .status()
+                .map_err(anyhow::Error::from)
+                .and_then(|status| match status.success() {
+                    true => config::read_with(&draft).map(Some),
+                    false => Ok(None),
+                })
+                .and_then(|edited| {
+                    if let Some(edited) = &edited {
+                        check_filter(edited)?;
+                    }
+                    Ok(edited)
+                });
+            match checked {
+                Ok(Some(edited)) => {
+                    fs::rename(&draft, &path)?;
+                    config::lock_filter(&edited.filter)?;
+                }
+                other => {
+                    let _ = fs::remove_file(&draft);
+                    other?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
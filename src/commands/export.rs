@@ -0,0 +1,219 @@
+use std::{
+    fs,
+    io::{stdout, Write},
+    path::PathBuf,
+};
+
+use animeku_cli::{
+    models::{Episode, Stream},
+    t,
+};
+use colored::Colorize;
+use futures::{stream, StreamExt};
+use html_escape::encode_text;
+
+use crate::{
+    animeku::{clearscreen_and_show_banner, AnimekuCli},
+    args::PlaylistFormat,
+    config,
+    input::get_user_input,
+};
+
+pub async fn run(
+    title: Option<String>,
+    tipe: Option<usize>,
+    format: Option<PlaylistFormat>,
+    output: Option<PathBuf>,
+    jobs: usize,
+) -> anyhow::Result<()> {
+    clearscreen_and_show_banner()?;
+    let input = get_user_input(title, tipe)?;
+    let animeku = AnimekuCli::new(input.tipe);
+
+    clearscreen_and_show_banner()?;
+    let movie = match input.movie {
+        Some(movie) => movie,
+        None => animeku.search(&input.title).await?,
+    };
+    clearscreen_and_show_banner()?;
+    let episodes = animeku.select_episodes(movie.clone()).await?;
+    if episodes.is_empty() {
+        return Ok(());
+    }
+
+    print!(
+        "{} {}",
+        "◆".blue(),
+        t!("export.progress", episodes.len().to_string().green())
+    );
+    stdout().flush()?;
+
+    let quality = config::get().quality.as_deref();
+    let pipeline = animeku.pipeline();
+    let resolved = stream::iter(episodes)
+        .map(|episode| async move {
+            let result = pipeline.resolve(episode.clone(), quality).await;
+            (episode, result)
+        })
+        .buffered(jobs.max(1))
+        .collect::<Vec<_>>()
+        .await;
+    println!("{}", t!("status.success"));
+
+    let mut entries = Vec::new();
+    for (episode, result) in resolved {
+        match result {
+            Ok(Some(stream)) => entries.push((episode, stream)),
+            Ok(None) => eprintln!(
+                "{} {}",
+                "■".red(),
+                t!("export.skipped", episode.title, t!("export.no_stream")).yellow()
+            ),
+            Err(e) => eprintln!(
+                "{} {}",
+                "■".red(),
+                t!("export.skipped", episode.title, format!("{:#}", e)).yellow()
+            ),
+        }
+    }
+
+    let format = format.unwrap_or_else(|| match output {
+        Some(ref path) if path.extension().is_some_and(|e| e == "xspf") => PlaylistFormat::Xspf,
+        _ => PlaylistFormat::M3u8,
+    });
+    let (body, extension) = match format {
+        PlaylistFormat::M3u8 => (m3u8(&entries), "m3u8"),
+        PlaylistFormat::Xspf => (xspf(&movie.title, &entries), "xspf"),
+    };
+
+    let path = output.unwrap_or_else(|| {
+        let name = movie
+            .title
+            .trim()
+            .replace(|c: char| !c.is_alphanumeric() && c != ' ' && c != '-', "");
+        config::get()
+            .download_dir
+            .clone()
+            .unwrap_or_default()
+            .join(format!("{}.{}", name, extension))
+    });
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, body)?;
+    let path = fs::canonicalize(&path).unwrap_or(path);
+    println!(
+        "{} {}",
+        "◆".blue(),
+        t!("export.saved", path.display().to_string().green())
+    );
+    Ok(())
+}
+
+/// Titles and header values on one line, a line break would start a new
+/// playlist entry.
+fn line(value: &str) -> String {
+    value.trim().replace(['\n', '\r'], " ")
+}
+
+fn m3u8(entries: &[(Episode, Stream)]) -> String {
+    let mut out = String::from("#EXTM3U\n");
+    for (episode, stream) in entries {
+        out.push_str(&format!("#EXTINF:-1,{}\n", line(&episode.title)));
+        for (key, value) in &stream.headers {
+            match key.to_lowercase().as_str() {
+                "user-agent" => {
+                    out.push_str(&format!("#EXTVLCOPT:http-user-agent={}\n", line(value)))
+                }
+                "referer" => out.push_str(&format!("#EXTVLCOPT:http-referrer={}\n", line(value))),
+                _ => {}
+            }
+        }
+        if !stream.headers.is_empty() {
+            let headers = stream
+                .headers
+                .iter()
+                .map(|(key, value)| (key.clone(), serde_json::Value::from(value.clone())))
+                .collect::<serde_json::Map<_, _>>();
+            out.push_str(&format!("#EXTHTTP:{}\n", serde_json::Value::from(headers)));
+        }
+        out.push_str(&line(&stream.url));
+        out.push('\n');
+    }
+    out
+}
+
+fn xspf(title: &str, entries: &[(Episode, Stream)]) -> String {
+    let mut out = format!(
+        concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<playlist version=\"1\" xmlns=\"http://xspf.org/ns/0/\" ",
+            "xmlns:vlc=\"http://www.videolan.org/vlc/playlist/ns/0/\">\n",
+            "  <title>{}</title>\n",
+            "  <trackList>\n"
+        ),
+        encode_text(&line(title))
+    );
+    for (episode, stream) in entries {
+        out.push_str("    <track>\n");
+        out.push_str(&format!(
+            "      <location>{}</location>\n",
+            encode_text(&stream.url)
+        ));
+        out.push_str(&format!(
+            "      <title>{}</title>\n",
+            encode_text(&line(&episode.title))
+        ));
+        let options = stream
+            .headers
+            .iter()
+            .filter_map(|(key, value)| match key.to_lowercase().as_str() {
+                "user-agent" => Some(format!("http-user-agent={}", value)),
+                "referer" => Some(format!("http-referrer={}", value)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        if !options.is_empty() {
+            out.push_str(
+                "      <extension application=\"http://www.videolan.org/vlc/playlist/0\">\n",
+            );
+            for option in options {
+                out.push_str(&format!(
+                    "        <vlc:option>{}</vlc:option>\n",
+                    encode_text(&option)
+                ));
+            }
+            out.push_str("      </extension>\n");
+        }
+        out.push_str("    </track>\n");
+    }
+    out.push_str("  </trackList>\n</playlist>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn titles_cannot_break_entries() {
+        let episode = Episode {
+            id: "1".into(),
+            title: "Ep 1\r\nhttp://evil/".into(),
+            is_series: true,
+        };
+        let stream = Stream {
+            url: "http://host/ep1.m3u8".into(),
+            headers: vec![("Referer".into(), "http://host/\n#EXTINF:-1,x".into())],
+            ..Default::default()
+        };
+        let entries = [(episode, stream)];
+
+        let m3u8 = m3u8(&entries);
+        assert_eq!(m3u8.lines().count(), 5);
+        assert!(m3u8.contains("#EXTINF:-1,Ep 1  http://evil/\n"));
+        assert!(m3u8.contains("#EXTVLCOPT:http-referrer=http://host/ #EXTINF:-1,x\n"));
+        assert!(xspf("A\nB", &entries).contains("<title>A B</title>"));
+        assert!(xspf("A", &entries).contains("<title>Ep 1  http://evil/</title>"));
+    }
+}
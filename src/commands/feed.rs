@@ -0,0 +1,29 @@
+use std::{fs, path::PathBuf};
+
+use animeku_cli::t;
+use colored::Colorize;
+
+use crate::{config, feed::Feed, server::playlist::play_url};
+
+pub fn run(output: Option<PathBuf>, base_url: Option<String>) -> anyhow::Result<()> {
+    let server = &config::get().server;
+    let base = base_url
+        .map(|url| url.trim_end_matches('/').to_string())
+        .unwrap_or_else(|| format!("http://localhost:{}", server.port));
+    let body = Feed::load().atom(&format!("{}/feed.atom", base), |item| {
+        play_url(&base, &item.source, &item.episode, server.token.as_deref())
+    });
+
+    match output {
+        Some(path) => {
+            fs::write(&path, body)?;
+            eprintln!(
+                "{} {}",
+                "◆".blue(),
+                t!("feed.saved", path.display().to_string().green())
+            );
+        }
+        None => print!("{}", body),
+    }
+    Ok(())
+}
@@ -0,0 +1,253 @@
+use std::{fs, path::PathBuf};
+
+use animeku_cli::{models::Movie, pipeline::Pipeline, t};
+use colored::Colorize;
+use dialoguer::theme::ColorfulTheme;
+use futures::{stream, StreamExt};
+use serde_json::json;
+
+use crate::{
+    args::{HistoryAction, ListFormat},
+    config,
+    history::History,
+    mal::{self, Anime, Match, Review},
+    watchlist::{Entry, Watchlist},
+};
+
+static IMPORT_SOURCE: &str = "anime";
+
+pub async fn run(action: HistoryAction) -> anyhow::Result<()> {
+    match action {
+        HistoryAction::Export { format, output } => export(format, output),
+        HistoryAction::Import { file, jobs } => import(file, jobs).await,
+        HistoryAction::Review => review().await,
+    }
+}
+
+/// Watchlist entries plus the shows that only appear in the history.
+fn shows(history: &History) -> Vec<Entry> {
+    let mut entries = Watchlist::load().entries;
+    for entry in entries.iter_mut() {
+        entry.progress = entry
+            .progress
+            .max(history.progress(&entry.source, &entry.id));
+    }
+    for watched in &history.entries {
+        if !entries
+            .iter()
+            .any(|e| e.source == watched.source && e.id == watched.show_id)
+        {
+            let movie = Movie {
+                id: watched.show_id.clone(),
+                title: watched.show_title.clone(),
+                ..Default::default()
+            };
+            let mut entry = Entry::new(&watched.source, &movie, 0);
+            entry.progress = history.progress(&watched.source, &watched.show_id);
+            entry.added = watched.watched;
+            entries.push(entry);
+        }
+    }
+    entries
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn csv(entries: &[Entry]) -> String {
+    let mut out = String::from("source,id,title,episodes,progress,status,tags\n");
+    for entry in entries {
+        let row = [
+            entry.source.clone(),
+            entry.id.clone(),
+            entry.title.clone(),
+            entry.episodes.to_string(),
+            entry.progress.to_string(),
+            mal::status(entry).to_string(),
+            entry.tags.join(";"),
+        ];
+        out.push_str(
+            &row.iter()
+                .map(|v| csv_field(v))
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+        out.push('\n');
+    }
+    out
+}
+
+fn export(format: ListFormat, output: Option<PathBuf>) -> anyhow::Result<()> {
+    let history = History::load();
+    let entries = shows(&history);
+    let (body, name) = match format {
+        ListFormat::Mal => (mal::to_xml(&entries), "animelist.xml"),
+        ListFormat::Json => (
+            serde_json::to_string_pretty(&json!({
+                "watchlist": entries,
+                "history": history.entries,
+            }))?,
+            "animeku.json",
+        ),
+        ListFormat::Csv => (csv(&entries), "animeku.csv"),
+    };
+
+    // Without `download_dir` the default file lands in the current folder.
+    let path = output.unwrap_or_else(|| {
+        config::get()
+            .download_dir
+            .clone()
+            .unwrap_or_default()
+            .join(name)
+    });
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, body)?;
+    let path = fs::canonicalize(&path).unwrap_or(path);
+    println!(
+        "{} {}",
+        "◆".blue(),
+        t!(
+            "history.exported",
+            entries.len(),
+            path.display().to_string().green()
+        )
+    );
+    Ok(())
+}
+
+async fn import(file: PathBuf, jobs: usize) -> anyhow::Result<()> {
+    let animes = mal::parse(&fs::read_to_string(&file)?);
+    println!(
+        "{} {}",
+        "◆".blue(),
+        t!("history.importing", animes.len().to_string().green())
+    );
+
+    let pipeline = Pipeline::from_source(IMPORT_SOURCE, &config::get().ext())?;
+    let pipeline = &pipeline;
+    let results = stream::iter(animes)
+        .map(|anime| async move {
+            let found = mal::find(pipeline, &anime).await;
+            (anime, found)
+        })
+        .buffered(jobs.max(1))
+        .collect::<Vec<_>>()
+        .await;
+
+    let mut watchlist = Watchlist::load();
+    let mut queue = Review::load();
+    let (mut matched, mut queued) = (0, 0);
+    for (anime, found) in results {
+        match found {
+            Ok(Match::Found(movie)) => {
+                mal::apply(&mut watchlist, IMPORT_SOURCE, &movie, &anime);
+                matched += 1;
+            }
+            Ok(Match::Review(candidates)) => {
+                queue.push(anime, &candidates);
+                queued += 1;
+            }
+            Err(e) => {
+                eprintln!(
+                    "{} {}",
+                    "■".red(),
+                    t!("history.queued", anime.title, format!("{:#}", e)).yellow()
+                );
+                queue.push(anime, &[]);
+                queued += 1;
+            }
+        }
+    }
+    watchlist.save()?;
+    queue.save()?;
+
+    println!("{} {}", "◆".blue(), t!("history.imported", matched, queued));
+    Ok(())
+}
+
+async fn choose(
+    pipeline: &Pipeline,
+    anime: &Anime,
+    candidates: Vec<Movie>,
+) -> anyhow::Result<Option<Option<Movie>>> {
+    let mut items = candidates
+        .iter()
+        .map(|m| m.title.trim().to_string())
+        .collect::<Vec<_>>();
+    items.push(t!("history.review.search").into());
+    items.push(t!("history.review.skip").into());
+    items.push(t!("history.review.drop").into());
+
+    let selected = dialoguer::FuzzySelect::with_theme(&ColorfulTheme::default())
+        .with_prompt(t!(
+            "history.review.prompt",
+            anime.title.as_str().green(),
+            anime.watched
+        ))
+        .default(0)
+        .max_length(8)
+        .items(&items)
+        .interact()?;
+
+    if let Some(movie) = candidates.get(selected) {
+        return Ok(Some(Some(movie.clone())));
+    }
+    match selected - candidates.len() {
+        0 => {
+            let title: String = dialoguer::Input::with_theme(&ColorfulTheme::default())
+                .with_prompt(t!("input.title"))
+                .with_initial_text(anime.title.as_str())
+                .interact_text()?;
+            let (movies, _) = pipeline.search(&title, 1).await?;
+            Box::pin(choose(pipeline, anime, movies)).await
+        }
+        1 => Ok(None),
+        _ => Ok(Some(None)),
+    }
+}
+
+async fn review() -> anyhow::Result<()> {
+    let pipeline = Pipeline::from_source(IMPORT_SOURCE, &config::get().ext())?;
+    let pending = Review::load().items;
+    if pending.is_empty() {
+        println!("{} {}", "◆".blue(), t!("history.review.empty"));
+        return Ok(());
+    }
+
+    for item in pending {
+        let candidates = item
+            .candidates
+            .iter()
+            .map(|(id, title)| Movie {
+                id: id.clone(),
+                title: title.clone(),
+                ..Default::default()
+            })
+            .collect();
+        let Some(choice) = choose(&pipeline, &item.anime, candidates).await? else {
+            continue;
+        };
+
+        if let Some(movie) = choice {
+            let mut watchlist = Watchlist::load();
+            mal::apply(&mut watchlist, IMPORT_SOURCE, &movie, &item.anime);
+            watchlist.save()?;
+            println!(
+                "{} {}",
+                "◆".blue(),
+                t!("favorite.added", movie.title.trim())
+            );
+        }
+        let mut queue = Review::load();
+        queue.items.retain(|p| p.anime.title != item.anime.title);
+        queue.save()?;
+    }
+    Ok(())
+}
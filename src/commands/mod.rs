@@ -0,0 +1,8 @@
+pub mod calendar;
+pub mod check;
+pub mod config;
+pub mod export;
+pub mod feed;
+pub mod history;
+pub mod profile;
+pub mod sync;
@@ -0,0 +1,140 @@
+use std::{
+    fs,
+    io::{stdin, IsTerminal},
+};
+
+use animeku_cli::t;
+use anyhow::bail;
+use colored::Colorize;
+use dialoguer::theme::ColorfulTheme;
+
+use crate::{
+    args::ProfileAction,
+    config,
+    profile::{hash_pin, profile_domain, Profile, Profiles, DEFAULT_PROFILE},
+};
+
+pub fn ask_pin(prompt: &str, confirm: bool) -> anyhow::Result<String> {
+    let theme = ColorfulTheme::default();
+    let mut input = dialoguer::Password::with_theme(&theme).with_prompt(prompt);
+    if confirm {
+        input = input.with_confirmation(t!("profile.pin_confirm"), t!("profile.pin_mismatch"));
+    }
+    Ok(input.interact()?)
+}
+
+/// Asks for a PIN up to three times until `check` accepts it.
+pub fn verify(prompt: &str, label: &str, check: impl Fn(&str) -> bool) -> anyhow::Result<()> {
+    if !stdin().is_terminal() {
+        bail!(t!("profile.pin_required", label));
+    }
+    for _ in 0..3 {
+        if check(&ask_pin(prompt, false)?) {
+            return Ok(());
+        }
+        eprintln!("{} {}", "■".red(), t!("profile.wrong_pin").yellow());
+    }
+    bail!(t!("profile.locked", label))
+}
+
+fn unlock(profile: &Profile) -> anyhow::Result<()> {
+    if profile.pin.is_none() {
+        return Ok(());
+    }
+    let label = t!("profile.label", profile.name);
+    verify(&t!("profile.pin", profile.name), &label, |pin| {
+        profile.check_pin(pin)
+    })
+}
+
+/// Activates the profile from `--profile`, or asks for one when `pick` is set
+/// and profiles exist.
+pub fn select(name: Option<String>, pick: bool) -> anyhow::Result<()> {
+    let profiles = Profiles::load();
+    let profile = match name {
+        Some(name) if name == DEFAULT_PROFILE => profiles.default_profile(),
+        Some(name) => match profiles.get(&name) {
+            Some(profile) => profile.clone(),
+            None => bail!(t!("profile.unknown", name)),
+        },
+        None if pick && !profiles.profiles.is_empty() && stdin().is_terminal() => {
+            let mut items = vec![profiles.default_profile().to_string()];
+            items.extend(profiles.profiles.iter().map(|p| p.to_string()));
+            let selected = dialoguer::Select::with_theme(&ColorfulTheme::default())
+                .with_prompt(t!("profile.choose"))
+                .default(0)
+                .items(&items)
+                .interact()?;
+            match selected {
+                0 => profiles.default_profile(),
+                i => profiles.profiles[i - 1].clone(),
+            }
+        }
+        None => profiles.default_profile(),
+    };
+
+    unlock(&profile)?;
+    if profile.name != DEFAULT_PROFILE {
+        config::set_profile(profile.name);
+    }
+    Ok(())
+}
+
+pub fn run(action: ProfileAction) -> anyhow::Result<()> {
+    let mut profiles = Profiles::load();
+    match action {
+        ProfileAction::List => {
+            println!("{}", profiles.default_profile());
+            for profile in &profiles.profiles {
+                println!("{}", profile);
+            }
+        }
+        ProfileAction::Add { name, pin } => {
+            let pin = if pin {
+                Some(ask_pin(t!("profile.new_pin"), true)?)
+            } else {
+                None
+            };
+            if name == DEFAULT_PROFILE {
+                bail!(t!("profile.exists", name));
+            }
+            profiles.add(&name, pin.as_deref())?;
+            profiles.save()?;
+            println!("{} {}", "◆".blue(), t!("profile.added", name.green()));
+        }
+        ProfileAction::Pin { name } => {
+            let profile = match profiles.get(&name) {
+                Some(profile) => profile.clone(),
+                None if name == DEFAULT_PROFILE => profiles.default_profile(),
+                None => bail!(t!("profile.unknown", name)),
+            };
+            unlock(&profile)?;
+            let pin = ask_pin(t!("profile.new_pin"), true)?;
+            let hash = (!pin.is_empty()).then(|| hash_pin(&profile_domain(&name), &pin));
+            match profiles.get_mut(&name) {
+                Some(profile) => profile.pin = hash,
+                None => profiles.default_pin = hash,
+            }
+            profiles.save()?;
+            println!("{} {}", "◆".blue(), t!("profile.pin_saved", name.green()));
+        }
+        ProfileAction::Remove { name } => {
+            let Some(profile) = profiles.get(&name).cloned() else {
+                bail!(t!("profile.unknown", name));
+            };
+            unlock(&profile)?;
+            if !dialoguer::Confirm::with_theme(&ColorfulTheme::default())
+                .with_prompt(t!("profile.confirm_remove", name))
+                .interact()?
+            {
+                return Ok(());
+            }
+            let _ = fs::remove_dir_all(profile.data_dir());
+            let _ = fs::remove_file(config::profile_config_path(&name));
+            profiles.remove(&name);
+            profiles.save()?;
+            println!("{} {}", "◆".blue(), t!("profile.removed", name.green()));
+        }
+    }
+    Ok(())
+}
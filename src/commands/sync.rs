@@ -0,0 +1,31 @@
+use std::io::{stdout, Write};
+
+use animeku_cli::t;
+use colored::Colorize;
+
+use crate::{config, sync};
+
+pub async fn run() -> anyhow::Result<()> {
+    print!("{} {}", "◆".blue(), t!("sync.progress"));
+    stdout().flush()?;
+    match sync::sync().await {
+        Ok(()) => {
+            println!("{}", t!("status.success"));
+            Ok(())
+        }
+        Err(e) => {
+            println!("{}", t!("status.failed"));
+            Err(e)
+        }
+    }
+}
+
+/// Syncs when `sync.auto` is on, a failure only prints a warning.
+pub async fn auto() {
+    if !config::get().sync.auto {
+        return;
+    }
+    if let Err(e) = run().await {
+        eprintln!("{} {}", "■".red(), format!("{:#}", e).yellow());
+    }
+}
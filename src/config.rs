@@ -0,0 +1,471 @@
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use animeku_cli::{
+    ext::{config::ExtConfig, mirror},
+    t,
+};
+use anyhow::{anyhow, bail};
+use once_cell::sync::OnceCell;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+pub use animeku_cli::ext::config::{FilterConfig, NetworkConfig, SourceConfig};
+
+static CONFIG: OnceCell<Config> = OnceCell::new();
+static CONFIG_PATH: OnceCell<PathBuf> = OnceCell::new();
+static PROFILE: OnceCell<String> = OnceCell::new();
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Config {
+    pub source: Option<String>,
+    pub quality: Option<String>,
+    pub language: Option<String>,
+    pub download_dir: Option<PathBuf>,
+    pub tui: bool,
+    pub player: PlayerConfig,
+    pub network: NetworkConfig,
+    pub thumbnail: ThumbnailConfig,
+    pub server: ServerConfig,
+    pub cast: CastConfig,
+    pub follow: FollowConfig,
+    pub anilist: AnilistConfig,
+    pub metadata: MetadataConfig,
+    pub sync: SyncConfig,
+    pub filter: FilterConfig,
+    pub sources: HashMap<String, SourceConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PlayerConfig {
+    pub command: String,
+    pub args: Vec<String>,
+    pub restream: bool,
+}
+
+impl Default for PlayerConfig {
+    fn default() -> Self {
+        Self {
+            command: "mpv".into(),
+            args: Vec::new(),
+            restream: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThumbnailMode {
+    #[default]
+    Auto,
+    Off,
+    Ascii,
+    Halfblock,
+    Kitty,
+    Iterm,
+    Sixel,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ThumbnailConfig {
+    pub enabled: bool,
+    pub mode: ThumbnailMode,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+}
+
+impl Default for ThumbnailConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            mode: ThumbnailMode::default(),
+            width: None,
+            height: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ServerConfig {
+    pub host: String,
+    pub port: u16,
+    pub token: Option<String>,
+    pub cors_origins: Vec<String>,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            host: "127.0.0.1".into(),
+            port: 8080,
+            token: None,
+            cors_origins: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CastConfig {
+    pub renderers: Vec<String>,
+    pub timeout: u64,
+}
+
+impl Default for CastConfig {
+    fn default() -> Self {
+        Self {
+            renderers: Vec::new(),
+            timeout: 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct FollowConfig {
+    pub interval: u64,
+    pub notify: bool,
+    pub download: bool,
+    pub library: Option<PathBuf>,
+}
+
+impl Default for FollowConfig {
+    fn default() -> Self {
+        Self {
+            interval: 60,
+            notify: true,
+            download: false,
+            library: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AnilistConfig {
+    pub token: Option<String>,
+    pub endpoint: String,
+}
+
+impl Default for AnilistConfig {
+    fn default() -> Self {
+        Self {
+            token: None,
+            endpoint: "https://graphql.anilist.co".into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnimeProvider {
+    #[default]
+    Anilist,
+    Jikan,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct MetadataConfig {
+    pub enabled: bool,
+    pub anime: AnimeProvider,
+    pub jikan_endpoint: String,
+    pub tmdb_key: Option<String>,
+    pub tmdb_endpoint: String,
+}
+
+impl Default for MetadataConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            anime: AnimeProvider::default(),
+            jikan_endpoint: "https://api.jikan.moe/v4".into(),
+            tmdb_key: None,
+            tmdb_endpoint: "https://api.themoviedb.org/3".into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SyncConfig {
+    pub dir: Option<PathBuf>,
+    pub webdav: Option<String>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub auto: bool,
+}
+
+impl Config {
+    /// Settings handed to the extractors; mirror redirects are remembered in
+    /// the shared data folder.
+    pub fn ext(&self) -> ExtConfig {
+        let mut network = self.network.clone();
+        network.redirects = Some(base_data_dir().join("redirects.json"));
+        ExtConfig {
+            network,
+            filter: self.filter.clone(),
+            sources: self.sources.clone(),
+        }
+    }
+}
+
+pub fn config_dir() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("animeku-cli")
+}
+
+/// Data folder shared by every profile.
+pub fn base_data_dir() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("animeku-cli")
+}
+
+/// Data folder of the active profile.
+pub fn data_dir() -> PathBuf {
+    match profile() {
+        Some(name) => base_data_dir().join("profiles").join(name),
+        None => base_data_dir(),
+    }
+}
+
+pub fn cache_dir() -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("animeku-cli")
+}
+
+fn main_config_path() -> PathBuf {
+    CONFIG_PATH
+        .get()
+        .cloned()
+        .unwrap_or_else(|| config_dir().join("config.toml"))
+}
+
+pub fn profile_config_path(name: &str) -> PathBuf {
+    config_dir().join("profiles").join(format!("{}.toml", name))
+}
+
+/// The file `config` edits: the profile overrides when a profile is active.
+pub fn config_path() -> PathBuf {
+    match profile() {
+        Some(name) => profile_config_path(name),
+        None => main_config_path(),
+    }
+}
+
+pub fn set_config_path(path: PathBuf) {
+    let _ = CONFIG_PATH.set(path);
+}
+
+pub fn set_profile(name: String) {
+    let _ = PROFILE.set(name);
+}
+
+pub fn profile() -> Option<&'static str> {
+    PROFILE.get().map(String::as_str)
+}
+
+fn read_table(path: &PathBuf) -> anyhow::Result<toml::Table> {
+    if !path.exists() {
+        return Ok(toml::Table::new());
+    }
+    let content = fs::read_to_string(path)?;
+    toml::from_str(&content).map_err(|e| anyhow!("{}: {}", path.display(), e))
+}
+
+fn merge_table(base: &mut toml::Table, overrides: toml::Table) {
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(value)) => merge_table(base, value),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Keys of `config` that differ from `base`.
+fn diff_table(config: toml::Table, base: &toml::Table) -> toml::Table {
+    let mut diff = toml::Table::new();
+    for (key, value) in config {
+        match (value, base.get(&key)) {
+            (toml::Value::Table(value), Some(toml::Value::Table(base))) => {
+                let nested = diff_table(value, base);
+                if !nested.is_empty() {
+                    diff.insert(key, toml::Value::Table(nested));
+                }
+            }
+            (value, Some(base)) if &value == base => {}
+            (value, _) => {
+                diff.insert(key, value);
+            }
+        }
+    }
+    diff
+}
+
+fn read_main() -> anyhow::Result<Config> {
+    let path = main_config_path();
+    read_table(&path)?
+        .try_into()
+        .map_err(|e| anyhow!("{}: {}", path.display(), e))
+}
+
+/// Copy of a mandatory filter, kept in the shared data folder so neither a
+/// profile nor `--config` can drop it.
+fn locked_filter_path() -> PathBuf {
+    base_data_dir().join("filter.json")
+}
+
+fn locked_filter() -> Option<FilterConfig> {
+    fs::read(locked_filter_path())
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+}
+
+/// Replaces the filter of `config` with the mandatory one, if any.
+fn with_locked_filter(mut config: Config) -> Config {
+    if let Some(filter) = locked_filter() {
+        config.filter = FilterConfig {
+            enabled: true,
+            ..filter
+        };
+    }
+    config
+}
+
+/// The config as written in the files, without the mandatory filter.
+pub fn read_unlocked() -> anyhow::Result<Config> {
+    read_with(&config_path())
+}
+
+/// Like `read_unlocked`, with `path` read in place of `config_path()`.
+pub fn read_with(path: &PathBuf) -> anyhow::Result<Config> {
+    let mut table = match profile() {
+        Some(_) => read_table(&main_config_path())?,
+        None => toml::Table::new(),
+    };
+    merge_table(&mut table, read_table(path)?);
+    table
+        .try_into()
+        .map_err(|e| anyhow!("{}: {}", path.display(), e))
+}
+
+pub fn read() -> anyhow::Result<Config> {
+    read_unlocked().map(with_locked_filter)
+}
+
+/// Writes `config`, a profile only keeps the keys that differ from the main
+/// config.
+pub fn write(config: &Config) -> anyhow::Result<()> {
+    let path = config_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let content = match profile() {
+        Some(_) => {
+            let base = toml::Table::try_from(read_main()?)?;
+            toml::to_string_pretty(&diff_table(toml::Table::try_from(config)?, &base))?
+        }
+        None => toml::to_string_pretty(config)?,
+    };
+    fs::write(path, content)?;
+    lock_filter(&config.filter)
+}
+
+/// Stores `filter` as the mandatory filter, or drops the stored one when
+/// `filter` is not mandatory.
+pub fn lock_filter(filter: &FilterConfig) -> anyhow::Result<()> {
+    let path = locked_filter_path();
+    if filter.mandatory {
+        fs::create_dir_all(base_data_dir())?;
+        fs::write(path, serde_json::to_vec_pretty(filter)?)?;
+    } else if path.exists() {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+pub fn init(config: Config) {
+    let _ = CONFIG.set(with_locked_filter(config));
+}
+
+pub fn get() -> &'static Config {
+    CONFIG.get_or_init(|| with_locked_filter(read().unwrap_or_default()))
+}
+
+pub fn http_client() -> Client {
+    mirror::http_client(&get().ext().network)
+}
+
+pub fn get_value(config: &Config, key: &str) -> anyhow::Result<toml::Value> {
+    let mut value = toml::Value::try_from(config)?;
+    for part in key.split('.') {
+        value = value
+            .get(part)
+            .cloned()
+            .ok_or_else(|| anyhow!(t!("config.key_not_found", key)))?;
+    }
+    Ok(value)
+}
+
+pub fn set_value(config: &Config, key: &str, raw: &str) -> anyhow::Result<Config> {
+    let new_value = parse_value(raw);
+    let mut root = toml::Value::try_from(config)?;
+
+    let parts = key.split('.').collect::<Vec<_>>();
+    let (last, parents) = parts
+        .split_last()
+        .ok_or_else(|| anyhow!(t!("config.empty_key")))?;
+
+    let mut table = root
+        .as_table_mut()
+        .ok_or_else(|| anyhow!(t!("config.invalid")))?;
+    for part in parents {
+        table = table
+            .entry(part.to_string())
+            .or_insert_with(|| toml::Value::Table(Default::default()))
+            .as_table_mut()
+            .ok_or_else(|| anyhow!(t!("config.not_table", part)))?;
+    }
+    table.insert(last.to_string(), new_value);
+
+    match root.try_into() {
+        Ok(config) => Ok(config),
+        Err(e) => bail!(t!("config.invalid_value", key, e)),
+    }
+}
+
+pub fn unset_value(config: &Config, key: &str) -> anyhow::Result<Config> {
+    let mut root = toml::Value::try_from(config)?;
+
+    let parts = key.split('.').collect::<Vec<_>>();
+    let (last, parents) = parts
+        .split_last()
+        .ok_or_else(|| anyhow!(t!("config.empty_key")))?;
+
+    let mut value = &mut root;
+    for part in parents {
+        value = value
+            .get_mut(part)
+            .ok_or_else(|| anyhow!(t!("config.key_not_found", key)))?;
+    }
+    if let Some(table) = value.as_table_mut() {
+        table.remove(*last);
+    }
+    Ok(root.try_into()?)
+}
+
+fn parse_value(raw: &str) -> toml::Value {
+    let wrapped = format!("v = {}", raw);
+    match toml::from_str::<toml::Table>(&wrapped) {
+        Ok(mut table) => table.remove("v").unwrap_or(toml::Value::String(raw.into())),
+        Err(_) => toml::Value::String(raw.into()),
+    }
+}
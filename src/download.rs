@@ -0,0 +1,170 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use animeku_cli::{
+    models::{Episode, Stream},
+    t,
+    util::stream_request,
+};
+use anyhow::bail;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use tokio::{io::AsyncWriteExt, process::Command};
+
+use crate::config::{self, http_client};
+
+/// An episode waiting to be downloaded by `check`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Pending {
+    pub source: String,
+    pub title: String,
+    pub episode: Episode,
+}
+
+/// Auto-downloads that haven't succeeded yet, kept on this device only.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Queue {
+    pub pending: Vec<Pending>,
+}
+
+fn queue_path() -> PathBuf {
+    config::data_dir().join("downloads.json")
+}
+
+impl Queue {
+    pub fn load() -> Self {
+        fs::read(queue_path())
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        let path = queue_path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn push(&mut self, source: &str, title: &str, episode: &Episode) {
+        let queued = self
+            .pending
+            .iter()
+            .any(|p| p.source == source && p.episode.id == episode.id);
+        if !queued {
+            self.pending.push(Pending {
+                source: source.into(),
+                title: title.into(),
+                episode: episode.clone(),
+            });
+        }
+    }
+
+    pub fn remove(&mut self, source: &str, episode_id: &str) {
+        self.pending
+            .retain(|p| p.source != source || p.episode.id != episode_id);
+    }
+}
+
+pub fn file_name(name: &str) -> String {
+    name.trim()
+        .replace(|c: char| !c.is_alphanumeric() && !" -_.()".contains(c), "")
+        .trim()
+        .to_string()
+}
+
+pub fn extension(stream: &Stream) -> &'static str {
+    let path = stream.url.split(['?', '#']).next().unwrap_or_default();
+    if path.ends_with(".mkv") {
+        "mkv"
+    } else {
+        "mp4"
+    }
+}
+
+pub async fn download(stream: &Stream, path: &Path) -> anyhow::Result<()> {
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let partial = path.with_extension("part");
+
+    if stream.url.contains(".m3u8") {
+        let headers = stream
+            .headers
+            .iter()
+            .map(|(key, value)| format!("{}: {}\r\n", key, value))
+            .collect::<String>();
+        let mut cmd = Command::new("ffmpeg");
+        cmd.args(["-loglevel", "error", "-y"]);
+        if !headers.is_empty() {
+            cmd.arg("-headers").arg(headers);
+        }
+        let status = cmd
+            .arg("-i")
+            .arg(&stream.url)
+            .args([
+                "-c",
+                "copy",
+                "-f",
+                extension(stream).replace("mkv", "matroska").as_str(),
+            ])
+            .arg(&partial)
+            .status()
+            .await?;
+        if !status.success() {
+            bail!(t!("player.failed", "ffmpeg"));
+        }
+    } else {
+        let resp = stream_request(&http_client(), stream)?
+            .send()
+            .await?
+            .error_for_status()?;
+        let mut file = tokio::fs::File::create(&partial).await?;
+        let mut body = resp.bytes_stream();
+        while let Some(chunk) = body.next().await {
+            file.write_all(&chunk?).await?;
+        }
+        file.flush().await?;
+    }
+
+    tokio::fs::rename(partial, path).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn episode(id: &str) -> Episode {
+        Episode {
+            id: id.into(),
+            title: id.into(),
+            is_series: true,
+        }
+    }
+
+    #[test]
+    fn queue_keeps_failed_episodes_once() {
+        let mut queue = Queue::default();
+        queue.push("anime", "A", &episode("1"));
+        queue.push("anime", "A", &episode("2"));
+        // A failed download stays queued and the next check queues it again.
+        queue.push("anime", "A", &episode("1"));
+        queue.push("movie", "B", &episode("1"));
+        assert_eq!(queue.pending.len(), 3);
+
+        queue.remove("anime", "1");
+        let left = queue
+            .pending
+            .iter()
+            .map(|p| (p.source.as_str(), p.episode.id.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(left, [("anime", "2"), ("movie", "1")]);
+    }
+}
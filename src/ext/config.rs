@@ -0,0 +1,103 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// Settings the extractors need, passed in explicitly by the caller.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ExtConfig {
+    pub network: NetworkConfig,
+    pub filter: FilterConfig,
+    pub sources: HashMap<String, SourceConfig>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct NetworkConfig {
+    pub proxy: Option<String>,
+    pub doh: Option<String>,
+    /// Where permanent mirror redirects are remembered; in memory when unset.
+    #[serde(skip)]
+    pub redirects: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct FilterConfig {
+    pub enabled: bool,
+    pub genres: Vec<String>,
+    pub ratings: Vec<String>,
+    /// Salted PBKDF2 hash of the PIN, required to change the filter.
+    pub pin: Option<String>,
+    /// Applies the filter to every profile and config file.
+    pub mandatory: bool,
+}
+
+impl Default for FilterConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            genres: vec!["Ecchi".into(), "Hentai".into()],
+            ratings: vec!["R+".into(), "Rx".into(), "R18".into(), "18+".into()],
+            pin: None,
+            mandatory: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SourceConfig {
+    pub base_urls: Vec<String>,
+    pub paths: HashMap<String, String>,
+}
+
+pub fn default_source(name: &str) -> SourceConfig {
+    let (base_urls, paths): (&[&str], &[(&str, &str)]) = match name {
+        "nontonanime" => (
+            &["https://animeku.my.id/nontonanime-v77"],
+            &[
+                (
+                    "search_category",
+                    "phalcon/api/search_category_collection/v7_1/",
+                ),
+                ("search_movie", "phalcon/api/search_anime_movie/v7_1/"),
+                (
+                    "category_posts",
+                    "phalcon/api/get_category_posts_secure/v9_1/",
+                ),
+                (
+                    "post_description",
+                    "phalcon/api/get_post_description_secure/v9_4/",
+                ),
+            ],
+        ),
+        "tenflix" => (&["https://tenflix.org"], &[]),
+        "kotakajaib" => (&["https://kotakajaib.me"], &[]),
+        _ => (&[], &[]),
+    };
+
+    SourceConfig {
+        base_urls: base_urls.iter().map(|s| s.to_string()).collect(),
+        paths: paths
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect(),
+    }
+}
+
+impl ExtConfig {
+    /// The built-in mirrors of `name` with the configured overrides applied.
+    pub fn source(&self, name: &str) -> SourceConfig {
+        let mut source = default_source(name);
+        if let Some(custom) = self.sources.get(name) {
+            if !custom.base_urls.is_empty() {
+                source.base_urls = custom.base_urls.clone();
+            }
+            for (k, v) in &custom.paths {
+                source.paths.insert(k.clone(), v.clone());
+            }
+        }
+        source
+    }
+}
@@ -0,0 +1,59 @@
+use std::net::{IpAddr, SocketAddr};
+
+use hyper::client::connect::dns::Name;
+use reqwest::{
+    dns::{Addrs, Resolve, Resolving},
+    Client,
+};
+use serde_json::Value;
+
+use crate::t;
+
+pub struct DohResolver {
+    client: Client,
+    endpoint: String,
+}
+
+impl DohResolver {
+    pub fn new(endpoint: String) -> Self {
+        Self {
+            client: Client::new(),
+            endpoint,
+        }
+    }
+}
+
+impl Resolve for DohResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let client = self.client.clone();
+        let endpoint = self.endpoint.clone();
+        let host = name.as_str().to_string();
+
+        Box::pin(async move {
+            let mut addrs = Vec::new();
+            for record_type in ["A", "AAAA"] {
+                let json = client
+                    .get(&endpoint)
+                    .query(&[("name", host.as_str()), ("type", record_type)])
+                    .header("Accept", "application/dns-json")
+                    .send()
+                    .await?
+                    .json::<Value>()
+                    .await?;
+
+                if let Some(answers) = json["Answer"].as_array() {
+                    for answer in answers {
+                        if let Some(Ok(ip)) = answer["data"].as_str().map(str::parse::<IpAddr>) {
+                            addrs.push(SocketAddr::new(ip, 0));
+                        }
+                    }
+                }
+            }
+
+            if addrs.is_empty() {
+                return Err(t!("doh.not_found", host).into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
@@ -0,0 +1,43 @@
+use crate::{ext::config::FilterConfig, models::Meta};
+
+static GENRE_KEYS: [&str; 3] = ["genre", "género", "tag"];
+static RATING_KEYS: [&str; 5] = ["rating", "rated", "usia", "age", "classif"];
+
+/// Whether `meta` carries a genre or an age rating the filter can check.
+pub fn rated(meta: &Meta) -> bool {
+    has_keys(meta, &GENRE_KEYS) || has_keys(meta, &RATING_KEYS)
+}
+
+fn has_keys(meta: &Meta, keys: &[&str]) -> bool {
+    meta.data.iter().any(|(k, _)| {
+        let k = k.to_lowercase();
+        keys.iter().any(|key| k.contains(key))
+    })
+}
+
+fn values<'a>(meta: &'a Meta, keys: &'a [&str]) -> impl Iterator<Item = &'a str> {
+    meta.data
+        .iter()
+        .filter(|(k, _)| {
+            let k = k.to_lowercase();
+            keys.iter().any(|key| k.contains(key))
+        })
+        .flat_map(|(_, v)| v.split([',', '/', '|', ';']))
+        .map(str::trim)
+}
+
+/// Whether `meta` has a blocked genre, or an age rating such as
+/// `R+ - Mild Nudity` starting with a blocked rating.
+pub fn blocked(filter: &FilterConfig, meta: &Meta) -> bool {
+    let genre =
+        values(meta, &GENRE_KEYS).any(|v| filter.genres.iter().any(|g| v.eq_ignore_ascii_case(g)));
+    let rating = values(meta, &RATING_KEYS).any(|v| {
+        filter.ratings.iter().any(|r| {
+            v.eq_ignore_ascii_case(r)
+                || v.to_lowercase()
+                    .strip_prefix(&r.to_lowercase())
+                    .is_some_and(|rest| rest.starts_with([' ', '-']))
+        })
+    });
+    genre || rating
+}
@@ -0,0 +1,194 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::Path,
+    sync::{Arc, Mutex, RwLock},
+};
+
+use anyhow::anyhow;
+use once_cell::sync::Lazy;
+use reqwest::{
+    redirect::{Attempt, Policy},
+    Client, Proxy, RequestBuilder, StatusCode, Url,
+};
+
+use crate::{
+    ext::{
+        config::{ExtConfig, NetworkConfig},
+        doh::DohResolver,
+    },
+    t,
+};
+
+static MOVED: Lazy<Mutex<HashMap<String, String>>> = Lazy::new(Default::default);
+
+static PARKED_MARKERS: &[&str] = &[
+    "domain is for sale",
+    "domain may be for sale",
+    "buy this domain",
+    "parkingcrew",
+    "sedoparking",
+    "bodis.com",
+    "domain parking",
+];
+
+pub struct Mirror {
+    bases: RwLock<Vec<String>>,
+    paths: HashMap<String, String>,
+}
+
+impl Mirror {
+    pub fn new(name: &str, config: &ExtConfig) -> Self {
+        let source = config.source(name);
+        if let Some(ref path) = config.network.redirects {
+            load_redirects(path);
+        }
+        let moved = MOVED.lock().unwrap();
+        let bases = source
+            .base_urls
+            .into_iter()
+            .map(|base| {
+                let base = base.trim_end_matches('/').to_string();
+                match moved.get(&origin_of(&base)) {
+                    Some(new_origin) => base.replacen(&origin_of(&base), new_origin, 1),
+                    None => base,
+                }
+            })
+            .collect();
+
+        Self {
+            bases: RwLock::new(bases),
+            paths: source.paths,
+        }
+    }
+
+    pub fn path(&self, key: &str) -> &str {
+        self.paths.get(key).map(|s| s.as_str()).unwrap_or_default()
+    }
+
+    pub async fn fetch<F>(&self, make: F) -> anyhow::Result<Vec<u8>>
+    where
+        F: Fn(&str) -> RequestBuilder,
+    {
+        let bases = self.bases.read().unwrap().clone();
+        let mut last_error = anyhow!(t!("mirror.unavailable"));
+
+        for base in bases {
+            match make(&base).send().await {
+                Ok(response) if !response.status().is_success() => {
+                    last_error = anyhow!(t!("mirror.status", base, response.status()));
+                }
+                Ok(response) => {
+                    let bytes = match response.bytes().await {
+                        Ok(bytes) => bytes,
+                        Err(e) => {
+                            last_error = e.into();
+                            continue;
+                        }
+                    };
+                    if is_parked(&bytes) {
+                        last_error = anyhow!(t!("mirror.parked", base));
+                        continue;
+                    }
+                    self.promote(&base);
+                    return Ok(bytes.to_vec());
+                }
+                Err(e) if e.is_connect() || e.is_timeout() => {
+                    last_error = e.into();
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Err(last_error)
+    }
+
+    fn promote(&self, base: &str) {
+        let origin = origin_of(base);
+        let new_base = match MOVED.lock().unwrap().get(&origin) {
+            Some(new_origin) => base.replacen(&origin, new_origin, 1),
+            None => base.to_string(),
+        };
+
+        let mut bases = self.bases.write().unwrap();
+        bases.retain(|b| b != base && *b != new_base);
+        bases.insert(0, new_base);
+    }
+}
+
+pub fn http_client(network: &NetworkConfig) -> Client {
+    let redirects = network.redirects.clone();
+    let mut builder = Client::builder().redirect(Policy::custom(move |attempt| {
+        follow_redirect(attempt, redirects.as_deref())
+    }));
+
+    if let Some(proxy) = network.proxy.as_ref().and_then(|p| Proxy::all(p).ok()) {
+        builder = builder.proxy(proxy);
+    }
+    if let Some(ref doh) = network.doh {
+        builder = builder.dns_resolver(Arc::new(DohResolver::new(doh.clone())));
+    }
+    builder.build().unwrap_or_default()
+}
+
+fn follow_redirect(attempt: Attempt, redirects: Option<&Path>) -> reqwest::redirect::Action {
+    if attempt.previous().len() > 10 {
+        return attempt.error("too many redirects");
+    }
+
+    if matches!(
+        attempt.status(),
+        StatusCode::MOVED_PERMANENTLY | StatusCode::PERMANENT_REDIRECT
+    ) {
+        if let Some(previous) = attempt.previous().last() {
+            remember_redirect(previous, attempt.url(), redirects);
+        }
+    }
+    attempt.follow()
+}
+
+fn remember_redirect(from: &Url, to: &Url, redirects: Option<&Path>) {
+    if from.host_str() == to.host_str() {
+        return;
+    }
+    let from = from.origin().ascii_serialization();
+    let to = to.origin().ascii_serialization();
+
+    let mut moved = MOVED.lock().unwrap();
+    if moved.get(&from) != Some(&to) {
+        moved.insert(from, to);
+        if let Some(path) = redirects {
+            save_redirects(path, &moved);
+        }
+    }
+}
+
+fn origin_of(url: &str) -> String {
+    Url::parse(url)
+        .map(|u| u.origin().ascii_serialization())
+        .unwrap_or_else(|_| url.to_string())
+}
+
+fn is_parked(bytes: &[u8]) -> bool {
+    let head = String::from_utf8_lossy(&bytes[..bytes.len().min(64 * 1024)]).to_lowercase();
+    PARKED_MARKERS.iter().any(|marker| head.contains(marker))
+}
+
+fn load_redirects(path: &Path) {
+    let saved: HashMap<String, String> = fs::read(path)
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default();
+    let mut moved = MOVED.lock().unwrap();
+    for (from, to) in saved {
+        moved.entry(from).or_insert(to);
+    }
+}
+
+fn save_redirects(path: &Path, moved: &HashMap<String, String>) {
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    if let Ok(json) = serde_json::to_vec_pretty(moved) {
+        let _ = fs::write(path, json);
+    }
+}
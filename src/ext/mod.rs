@@ -1,13 +1,45 @@
+use std::sync::Arc;
+
+use anyhow::anyhow;
 use async_trait::async_trait;
 
-use crate::models::{Episode, Meta, Movie, Stream};
+use crate::{
+    ext::config::ExtConfig,
+    models::{Episode, Meta, Movie, Stream},
+    t,
+};
 
+pub mod config;
+pub mod doh;
+pub mod filter;
+pub mod mirror;
 pub mod nontonanime;
 pub mod tenflix;
 
+pub static SOURCES: [&str; 3] = ["anime", "movie", "tenflix"];
+
+pub fn source_index(name: &str) -> anyhow::Result<usize> {
+    SOURCES
+        .iter()
+        .position(|s| s.eq_ignore_ascii_case(name))
+        .ok_or_else(|| anyhow!(t!("source.unknown", name, SOURCES.join(", "))))
+}
+
+/// Builds the extractor for a source. The content filter is applied by
+/// `Pipeline::for_source`.
+pub fn get_ext(id: usize, config: &ExtConfig) -> Arc<dyn Ext> {
+    if id == 0 {
+        Arc::new(nontonanime::anime::AnimeExt::new(config))
+    } else if id == 1 {
+        Arc::new(nontonanime::movie::MovieExt::new(config))
+    } else {
+        Arc::new(tenflix::TenflixExt::new(config))
+    }
+}
+
 #[async_trait]
-pub trait Ext {
-    async fn search(&mut self, title: String, page: usize) -> anyhow::Result<(Vec<Movie>, u64)>;
+pub trait Ext: Send + Sync {
+    async fn search(&self, title: String, page: usize) -> anyhow::Result<(Vec<Movie>, u64)>;
     async fn get_episodes(&self, movie: Movie) -> anyhow::Result<(Vec<Episode>, Meta)>;
     async fn get_stream_urls(&self, episode: Episode) -> anyhow::Result<Vec<Stream>>;
 }
@@ -1,53 +1,47 @@
 use async_trait::async_trait;
 use reqwest::Client;
-use serde_json::Value;
 
 use crate::{
-    ext::Ext,
+    ext::{
+        config::ExtConfig,
+        mirror::{http_client, Mirror},
+        Ext,
+    },
     models::{Episode, Meta, Movie, Stream},
 };
 
-use super::get_stream_urls;
+use super::{category_meta, get_stream_urls, post_api};
 
 pub struct AnimeExt {
     pub client: Client,
+    mirror: Mirror,
 }
 
 impl AnimeExt {
-    pub fn new() -> Self {
+    pub fn new(config: &ExtConfig) -> Self {
         Self {
-            client: Client::new(),
+            client: http_client(&config.network),
+            mirror: Mirror::new("nontonanime", config),
         }
     }
 }
 
+impl Default for AnimeExt {
+    fn default() -> Self {
+        Self::new(&ExtConfig::default())
+    }
+}
+
 #[async_trait]
 impl Ext for AnimeExt {
-    async fn search(&mut self, title: String, page: usize) -> anyhow::Result<(Vec<Movie>, u64)> {
-        let url =
-            "https://animeku.my.id/nontonanime-v77/phalcon/api/search_category_collection/v7_1/";
+    async fn search(&self, title: String, page: usize) -> anyhow::Result<(Vec<Movie>, u64)> {
         let payload = format!(
             "search={}&page={}&count=20&lang=All&isAPKvalid=true",
             title, page
         );
 
-        let response = self
-            .client
-            .post(url)
-            .header("Cache-Control", "max-age=0")
-            .header("Data-Agent", "New Aniplex v9.1")
-            .header("Accept-Encoding", "gzip")
-            .header("Content-Type", "application/x-www-form-urlencoded")
-            .header("Content-Length", payload.len())
-            .header("Host", "animeku.my.id")
-            .header("Connection", "Keep-Alive")
-            .header("User-Agent", "okhttp/3.12.13")
-            .body(payload)
-            .send()
-            .await?;
-
+        let json = post_api(&self.client, &self.mirror, "search_category", payload).await?;
         let mut items = Vec::new();
-        let json = response.json::<Value>().await?;
 
         if let Some(categories) = json["categories"].as_array() {
             for cat in categories {
@@ -61,6 +55,7 @@ impl Ext for AnimeExt {
                         total_episodes: Some(
                             cat["count_anime"].as_str().unwrap_or("1").to_string(),
                         ),
+                        meta: category_meta(cat),
                     };
 
                     items.push(item)
@@ -72,49 +67,12 @@ impl Ext for AnimeExt {
     }
 
     async fn get_episodes(&self, movie: Movie) -> anyhow::Result<(Vec<Episode>, Meta)> {
-        let url =
-            "https://animeku.my.id/nontonanime-v77/phalcon/api/get_category_posts_secure/v9_1/";
         let payload = format!("id={}&isAPKvalid=true", movie.id);
 
-        let response = self
-            .client
-            .post(url)
-            .header("Cache-Control", "max-age=0")
-            .header("Data-Agent", "New Aniplex v9.1")
-            .header("Accept-Encoding", "gzip")
-            .header("Content-Type", "application/x-www-form-urlencoded")
-            .header("Content-Length", payload.len())
-            .header("Host", "animeku.my.id")
-            .header("Connection", "Keep-Alive")
-            .header("User-Agent", "okhttp/3.12.13")
-            .body(payload)
-            .send()
-            .await?;
-
         let mut episodes = Vec::new();
-        let mut meta = Meta::default();
-
-        let json = response.json::<Value>().await?;
 
-        if let Some(category) = json["category"].as_object() {
-            if let Some(thumb) = category.get("img_url") {
-                meta.thumb_url = Some(thumb.to_string().trim_matches('"').trim().into());
-            }
-            for (key, value) in [
-                ("Judul", "category_name"),
-                ("Genre", "genre"),
-                ("Tahun", "years"),
-                ("Rating", "rating"),
-            ] {
-                if let Some(v) = category.get(value) {
-                    meta.data
-                        .push((key.into(), v.to_string().trim_matches('"').trim().into()));
-                }
-            }
-            if let Some(v) = category["ongoing"].as_u64() {
-                meta.data.push(("On-Going".into(), (v != 0).to_string()));
-            }
-        }
+        let json = post_api(&self.client, &self.mirror, "category_posts", payload).await?;
+        let meta = category_meta(&json["category"]);
 
         if let Some(posts) = json["posts"].as_array() {
             for post in posts {
@@ -137,6 +95,6 @@ impl Ext for AnimeExt {
     }
 
     async fn get_stream_urls(&self, episode: Episode) -> anyhow::Result<Vec<Stream>> {
-        get_stream_urls(&self.client, episode).await
+        get_stream_urls(&self.client, &self.mirror, episode).await
     }
 }
@@ -2,7 +2,8 @@ use reqwest::Client;
 use serde_json::Value;
 
 use crate::{
-    models::{Episode, Stream},
+    ext::mirror::Mirror,
+    models::{Episode, Meta, Stream},
     util::{get_filesize, get_real_url},
 };
 
@@ -11,25 +12,61 @@ pub mod movie;
 
 static USER_PASS: &str = "drakornicojanuar:DIvANTArtBInsTriSkEremeNtOMICErCeSMiQUaKarypsBoari";
 
-pub async fn get_stream_urls(client: &Client, episode: Episode) -> anyhow::Result<Vec<Stream>> {
-    let url = "https://animeku.my.id/nontonanime-v77/phalcon/api/get_post_description_secure/v9_4/";
-    let payload = format!("channel_id={}&isAPKvalid=true", episode.id);
-
-    let response = client
-        .post(url)
-        .header("Cache-Control", "max-age=0")
-        .header("Data-Agent", "New Aniplex v9.1")
-        .header("Accept-Encoding", "gzip")
-        .header("Content-Type", "application/x-www-form-urlencoded")
-        .header("Content-Length", payload.len())
-        .header("Host", "animeku.my.id")
-        .header("Connection", "Keep-Alive")
-        .header("User-Agent", "okhttp/3.12.13")
-        .body(payload)
-        .send()
+pub async fn post_api(
+    client: &Client,
+    mirror: &Mirror,
+    path: &str,
+    payload: String,
+) -> anyhow::Result<Value> {
+    let path = mirror.path(path);
+    let bytes = mirror
+        .fetch(|base| {
+            client
+                .post(format!("{}/{}", base, path))
+                .header("Cache-Control", "max-age=0")
+                .header("Data-Agent", "New Aniplex v9.1")
+                .header("Accept-Encoding", "gzip")
+                .header("Content-Type", "application/x-www-form-urlencoded")
+                .header("Content-Length", payload.len())
+                .header("Connection", "Keep-Alive")
+                .header("User-Agent", "okhttp/3.12.13")
+                .body(payload.clone())
+        })
         .await?;
+    Ok(serde_json::from_slice(&bytes)?)
+}
 
-    let json = response.json::<Value>().await?;
+pub fn category_meta(category: &Value) -> Meta {
+    let mut meta = Meta::default();
+    if let Some(category) = category.as_object() {
+        if let Some(thumb) = category.get("img_url") {
+            meta.thumb_url = Some(thumb.to_string().trim_matches('"').trim().into());
+        }
+        for (key, value) in [
+            ("Judul", "category_name"),
+            ("Genre", "genre"),
+            ("Tahun", "years"),
+            ("Rating", "rating"),
+        ] {
+            if let Some(v) = category.get(value) {
+                meta.data
+                    .push((key.into(), v.to_string().trim_matches('"').trim().into()));
+            }
+        }
+        if let Some(v) = category.get("ongoing").and_then(Value::as_u64) {
+            meta.data.push(("On-Going".into(), (v != 0).to_string()));
+        }
+    }
+    meta
+}
+
+pub async fn get_stream_urls(
+    client: &Client,
+    mirror: &Mirror,
+    episode: Episode,
+) -> anyhow::Result<Vec<Stream>> {
+    let payload = format!("channel_id={}&isAPKvalid=true", episode.id);
+    let json = post_api(client, mirror, "post_description", payload).await?;
     let mut streams = vec![];
     for (n, reso) in [
         ("channel_url", "360p SD"),
@@ -56,7 +93,11 @@ pub async fn get_stream_urls(client: &Client, episode: Episode) -> anyhow::Resul
                     reso.push(')')
                 }
 
-                streams.push(Stream { url, title: reso });
+                streams.push(Stream {
+                    url,
+                    title: reso,
+                    headers: Vec::new(),
+                });
             }
         }
     }
@@ -1,56 +1,51 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::RwLock};
 
 use async_trait::async_trait;
 use reqwest::Client;
-use serde_json::Value;
 
 use crate::{
-    ext::Ext,
+    ext::{
+        config::ExtConfig,
+        mirror::{http_client, Mirror},
+        Ext,
+    },
     models::{Episode, Meta, Movie, Stream},
 };
 
-use super::get_stream_urls;
+use super::{get_stream_urls, post_api};
 
 pub struct MovieExt {
     pub client: Client,
-    metadata: HashMap<String, Meta>,
+    mirror: Mirror,
+    metadata: RwLock<HashMap<String, Meta>>,
 }
 
 impl MovieExt {
-    pub fn new() -> Self {
+    pub fn new(config: &ExtConfig) -> Self {
         Self {
-            client: Client::new(),
-            metadata: HashMap::new(),
+            client: http_client(&config.network),
+            mirror: Mirror::new("nontonanime", config),
+            metadata: RwLock::new(HashMap::new()),
         }
     }
 }
 
+impl Default for MovieExt {
+    fn default() -> Self {
+        Self::new(&ExtConfig::default())
+    }
+}
+
 #[async_trait]
 impl Ext for MovieExt {
-    async fn search(&mut self, title: String, page: usize) -> anyhow::Result<(Vec<Movie>, u64)> {
-        let url = "https://animeku.my.id/nontonanime-v77/phalcon/api/search_anime_movie/v7_1/";
+    async fn search(&self, title: String, page: usize) -> anyhow::Result<(Vec<Movie>, u64)> {
         let payload = format!(
             "search={}&page={}&count=20&lang=All&isAPKvalid=true",
             title, page
         );
 
-        let response = self
-            .client
-            .post(url)
-            .header("Cache-Control", "max-age=0")
-            .header("Data-Agent", "New Aniplex v9.1")
-            .header("Content-Type", "application/x-www-form-urlencoded")
-            .header("Content-Length", payload.len())
-            .header("Host", "animeku.my.id")
-            .header("Connection", "Keep-Alive")
-            .header("Accept-Encoding", "gzip")
-            .header("User-Agent", "okhttp/3.12.13")
-            .body(payload)
-            .send()
-            .await?;
-
+        let json = post_api(&self.client, &self.mirror, "search_movie", payload).await?;
         let mut items = Vec::new();
-        let json = response.json::<Value>().await?;
 
         if let Some(posts) = json["posts"].as_array() {
             for post in posts {
@@ -64,16 +59,19 @@ impl Ext for MovieExt {
 
                     for (key, value) in [
                         ("Judul", "channel_name"),
-                        ("Language", "lamg"),
+                        ("Bahasa", "lamg"),
                         ("Rating", "rating"),
-                        ("years", "years"),
+                        ("Tahun", "years"),
                     ] {
                         if let Some(v) = post.get(value) {
                             meta.data
                                 .push((key.into(), v.to_string().trim_matches('"').trim().into()))
                         }
                     }
-                    self.metadata.insert(id.to_string(), meta);
+                    self.metadata
+                        .write()
+                        .unwrap()
+                        .insert(id.to_string(), meta.clone());
 
                     let item = Movie {
                         id: id.to_string(),
@@ -83,6 +81,7 @@ impl Ext for MovieExt {
                             .trim()
                             .into(),
                         total_episodes: None,
+                        meta,
                     };
                     items.push(item)
                 }
@@ -93,20 +92,19 @@ impl Ext for MovieExt {
     }
 
     async fn get_episodes(&self, movie: Movie) -> anyhow::Result<(Vec<Episode>, Meta)> {
+        let meta = match self.metadata.read().unwrap().get(&movie.id) {
+            Some(v) => v.clone(),
+            None => movie.meta,
+        };
         let item = Episode {
-            id: movie.id.clone(),
+            id: movie.id,
             title: movie.title,
             is_series: false,
         };
-
-        if let Some(v) = self.metadata.get(&movie.id) {
-            Ok((vec![item], v.clone()))
-        } else {
-            Ok((vec![item], Meta::default()))
-        }
+        Ok((vec![item], meta))
     }
 
     async fn get_stream_urls(&self, episode: Episode) -> anyhow::Result<Vec<Stream>> {
-        get_stream_urls(&self.client, episode).await
+        get_stream_urls(&self.client, &self.mirror, episode).await
     }
 }
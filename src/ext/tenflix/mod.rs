@@ -1,5 +1,11 @@
+use std::collections::HashMap;
+
 use crate::{
-    ext::Ext,
+    ext::{
+        config::ExtConfig,
+        mirror::{http_client, Mirror},
+        Ext,
+    },
     models::{Episode, Meta, Movie, Stream},
     regex,
 };
@@ -10,38 +16,57 @@ use reqwest::Client;
 
 pub struct TenflixExt {
     client: Client,
+    mirror: Mirror,
+    kotakajaib: Mirror,
 }
 
 impl TenflixExt {
-    pub fn new() -> Self {
+    pub fn new(config: &ExtConfig) -> Self {
         Self {
-            client: Client::new(),
+            client: http_client(&config.network),
+            mirror: Mirror::new("tenflix", config),
+            kotakajaib: Mirror::new("kotakajaib", config),
         }
     }
+
+    async fn get_page(&self, path: &str) -> anyhow::Result<String> {
+        let bytes = self
+            .mirror
+            .fetch(|base| {
+                self.client
+                    .get(format!("{}/{}", base, path))
+                    .header("Content-Type", "application/x-www-form-urlencoded")
+                    .header("Connection", "Keep-Alive")
+                    .header("User-Agent", "okhttp/3.12.13")
+            })
+            .await?;
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+}
+
+impl Default for TenflixExt {
+    fn default() -> Self {
+        Self::new(&ExtConfig::default())
+    }
 }
 
 #[async_trait]
 impl Ext for TenflixExt {
-    async fn search(&mut self, title: String, page: usize) -> anyhow::Result<(Vec<Movie>, u64)> {
-        let url = format!("https://tenflix.org/page/{}/?s={}", page, title);
-
-        let response = self
-            .client
-            .get(url)
-            .header("Content-Type", "application/x-www-form-urlencoded")
-            .header("Host", "tenflix.org")
-            .header("Connection", "Keep-Alive")
-            .header("User-Agent", "okhttp/3.12.13")
-            .send()
+    async fn search(&self, title: String, page: usize) -> anyhow::Result<(Vec<Movie>, u64)> {
+        let body = self
+            .get_page(&format!("page/{}/?s={}", page, title))
             .await?;
 
-        let bytes = response.bytes().await?;
-        let body = String::from_utf8_lossy(&bytes);
-
         let mut movies = vec![];
 
+        let poster_re = regex!(r#"<a\s*href="https?://[^/"]+/([^"]+)">\s*<img[^>]+src="([^"]+)""#);
+        let posters = poster_re
+            .captures_iter(&body)
+            .map(|cap| (cap[1].to_string(), cap[2].to_string()))
+            .collect::<HashMap<_, _>>();
+
         let item = regex!(
-            r#"<a\s*href="https://tenflix.org/([^"]+)">\s*([^<]+)\s*</a>.*?*</div>.*?<div class="meta">.*?<span class="year">(\d+)</span>"#
+            r#"<a\s*href="https?://[^/"]+/([^"]+)">\s*([^<]+)\s*</a>.*?*</div>.*?<div class="meta">.*?<span class="year">(\d+)</span>"#
         );
         for cap in item.captures_iter(&body) {
             let id = &cap[1];
@@ -53,10 +78,19 @@ impl Ext for TenflixExt {
             } else {
                 "TV"
             };
+            let title = decode_html_entities(&title).trim().to_string();
+            let meta = Meta {
+                thumb_url: posters.get(id).cloned(),
+                data: vec![
+                    ("Judul".into(), title.clone()),
+                    ("Tahun".into(), year.into()),
+                ],
+            };
             let item = Movie {
                 id: id.to_string(),
-                title: format!("{} {} ({})", decode_html_entities(&title), year, tipe),
+                title: format!("{} {} ({})", title, year, tipe),
                 total_episodes: None,
+                meta,
             };
             movies.push(item);
         }
@@ -66,24 +100,12 @@ impl Ext for TenflixExt {
     }
 
     async fn get_episodes(&self, movie: Movie) -> anyhow::Result<(Vec<Episode>, Meta)> {
-        let url = format!("https://tenflix.org/{}", movie.id);
-        let response = self
-            .client
-            .get(url)
-            .header("Content-Type", "application/x-www-form-urlencoded")
-            .header("Host", "tenflix.org")
-            .header("Connection", "Keep-Alive")
-            .header("User-Agent", "okhttp/3.12.13")
-            .send()
-            .await?;
-
-        let bytes = response.bytes().await?;
-        let body = String::from_utf8_lossy(&bytes);
+        let body = self.get_page(&movie.id).await?;
 
         let mut episodes = vec![];
         if movie.id.starts_with("tv") {
             let re = regex!(
-                r#"numerando['"]>\s*([^>]+)\s*<.*?episodiotitle.*?href=['"]https:\/\/tenflix.org\/([^>]+)['"]\s*>\s*([^>]+)\s*<"#
+                r#"numerando['"]>\s*([^>]+)\s*<.*?episodiotitle.*?href=['"]https?:\/\/[^\/'"]+\/([^>]+)['"]\s*>\s*([^>]+)\s*<"#
             );
 
             for cap in re.captures_iter(&body) {
@@ -128,18 +150,18 @@ impl Ext for TenflixExt {
 
     async fn get_stream_urls(&self, episode: Episode) -> anyhow::Result<Vec<Stream>> {
         let mut streams = vec![];
-        let url = format!("https://tenflix.org/{}", episode.id);
-        if let Some(embed_url) = get_download_link(&self.client, url).await? {
-            let response = self
-                .client
-                .get(embed_url)
-                .header("Content-Type", "application/x-www-form-urlencoded")
-                .header("Connection", "Keep-Alive")
-                .header("User-Agent", "okhttp/3.12.13")
-                .send()
+        let body = self.get_page(&episode.id).await?;
+        if let Some(file_id) = get_download_link(&self.client, &body).await? {
+            let bytes = self
+                .kotakajaib
+                .fetch(|base| {
+                    self.client
+                        .get(format!("{}/embed/{}", base, file_id))
+                        .header("Content-Type", "application/x-www-form-urlencoded")
+                        .header("Connection", "Keep-Alive")
+                        .header("User-Agent", "okhttp/3.12.13")
+                })
                 .await?;
-
-            let bytes = response.bytes().await?;
             let body = String::from_utf8_lossy(&bytes);
 
             let re3 = regex!(r#"item.*?id=['"]([^'"]+).*?data-frame=['"]([^'"]+).*?>([^<]+)<"#);
@@ -154,6 +176,7 @@ impl Ext for TenflixExt {
                     let item = Stream {
                         title: format!("{}: {}", &cap[1], &cap[3]),
                         url,
+                        headers: vec![("User-Agent".into(), "okhttp/3.12.13".into())],
                     };
 
                     streams.push(item);
@@ -203,7 +226,7 @@ async fn extract_private_server(client: &Client, url: String) -> String {
     url
 }
 
-async fn get_embed_link(client: &Client, url: &str) -> anyhow::Result<Option<String>> {
+async fn get_file_id(client: &Client, url: &str) -> anyhow::Result<Option<String>> {
     let response = client
         .get(url)
         .header("Content-Type", "application/x-www-form-urlencoded")
@@ -214,30 +237,19 @@ async fn get_embed_link(client: &Client, url: &str) -> anyhow::Result<Option<Str
     let bytes = response.bytes().await?;
     let body = String::from_utf8_lossy(&bytes);
 
-    let re = regex!(r#"href=['"](https:\/\/kotakajaib.me\/file\/[^/'"]+)"#);
+    let re = regex!(r#"href=['"]https?:\/\/[^\/'"]+\/file\/([^/'"]+)"#);
     if let Some(cap) = re.captures(&body) {
-        let embed = &cap[1].replace("file", "embed");
-        return Ok(Some(embed.to_string()));
+        return Ok(Some(cap[1].to_string()));
     }
     Ok(None)
 }
 
-async fn get_download_link(client: &Client, url: String) -> anyhow::Result<Option<String>> {
-    let response = client
-        .get(url)
-        .header("Content-Type", "application/x-www-form-urlencoded")
-        .header("Connection", "Keep-Alive")
-        .header("User-Agent", "okhttp/3.12.13")
-        .send()
-        .await?;
-    let bytes = response.bytes().await?;
-    let body = String::from_utf8_lossy(&bytes);
-
-    let re = regex!(r#"href=['"](https:\/\/tenflix.org\/links/[^/'"]+)"#);
-    for cap in re.captures_iter(&body) {
+async fn get_download_link(client: &Client, body: &str) -> anyhow::Result<Option<String>> {
+    let re = regex!(r#"href=['"](https?:\/\/[^\/'"]+\/links/[^/'"]+)"#);
+    for cap in re.captures_iter(body) {
         let url = &cap[1];
-        if let Some(embed) = get_embed_link(client, url).await? {
-            return Ok(Some(embed));
+        if let Some(file_id) = get_file_id(client, url).await? {
+            return Ok(Some(file_id));
         }
     }
     Ok(None)
@@ -0,0 +1,119 @@
+use animeku_cli::t;
+use colored::Colorize;
+use dialoguer::theme::ColorfulTheme;
+
+use crate::watchlist::{Entry, SortBy, Watchlist};
+
+fn sort_label(sort: SortBy) -> &'static str {
+    match sort {
+        SortBy::Added => t!("favorite.sort.added"),
+        SortBy::Updated => t!("favorite.sort.updated"),
+        SortBy::Title => t!("favorite.sort.title"),
+        SortBy::Source => t!("favorite.sort.source"),
+    }
+}
+
+pub fn menu() -> anyhow::Result<Option<Entry>> {
+    loop {
+        let mut watchlist = Watchlist::load();
+        let entries = watchlist.sorted();
+        if entries.is_empty() {
+            return Ok(None);
+        }
+
+        let mut items = entries.iter().map(|e| e.to_string()).collect::<Vec<_>>();
+        items.push(t!("favorite.sort", sort_label(watchlist.sort)));
+        items.push(t!("favorite.back").into());
+
+        let selected = dialoguer::Select::with_theme(&ColorfulTheme::default())
+            .with_prompt(t!("input.menu.favorites", entries.len()))
+            .default(0)
+            .max_length(10)
+            .items(&items)
+            .interact()?;
+
+        if selected == entries.len() {
+            watchlist.sort = watchlist.sort.next();
+            watchlist.save()?;
+            continue;
+        }
+        let Some(entry) = entries.get(selected) else {
+            return Ok(None);
+        };
+
+        let action = dialoguer::Select::with_theme(&ColorfulTheme::default())
+            .with_prompt(entry.title.as_str())
+            .default(0)
+            .item(t!("favorite.open"))
+            .item(t!("favorite.tags"))
+            .item(if entry.followed {
+                t!("follow.remove")
+            } else {
+                t!("follow.add")
+            })
+            .item(t!("favorite.delete"))
+            .item(t!("favorite.back"))
+            .interact()?;
+
+        match action {
+            0 => return Ok(Some(entry.clone())),
+            1 => {
+                let tags = edit_tags(&watchlist, entry)?;
+                if let Some(e) = watchlist.get_mut(&entry.source, &entry.id) {
+                    e.tags = tags;
+                }
+                watchlist.save()?;
+            }
+            2 => {
+                if entry.followed {
+                    watchlist.unfollow(&entry.source, &entry.id);
+                } else {
+                    // The next check takes the current episodes as seen.
+                    watchlist.follow(entry.clone(), Vec::new());
+                }
+                watchlist.save()?;
+                let message = if entry.followed {
+                    t!("follow.removed", entry.title)
+                } else {
+                    t!("follow.added", entry.title)
+                };
+                println!("{} {}", "◆".blue(), message);
+            }
+            3 => {
+                watchlist.remove(&entry.source, &entry.id);
+                watchlist.save()?;
+                println!("{} {}", "◆".blue(), t!("favorite.removed", entry.title));
+            }
+            _ => {}
+        }
+    }
+}
+
+fn edit_tags(watchlist: &Watchlist, entry: &Entry) -> anyhow::Result<Vec<String>> {
+    let known = watchlist.tags();
+    let checked = known
+        .iter()
+        .map(|tag| entry.tags.contains(tag))
+        .collect::<Vec<_>>();
+
+    let selected = dialoguer::MultiSelect::with_theme(&ColorfulTheme::default())
+        .with_prompt(t!("input.choose_many"))
+        .items(&known)
+        .defaults(&checked)
+        .interact()?;
+    let extra: String = dialoguer::Input::with_theme(&ColorfulTheme::default())
+        .with_prompt(t!("favorite.new_tags"))
+        .allow_empty(true)
+        .interact_text()?;
+
+    let mut tags = selected
+        .into_iter()
+        .map(|i| known[i].clone())
+        .collect::<Vec<_>>();
+    for tag in extra.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+        if !tags.iter().any(|t| t == tag) {
+            tags.push(tag.to_string());
+        }
+    }
+    Ok(tags)
+}
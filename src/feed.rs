@@ -0,0 +1,209 @@
+use std::{fs, path::PathBuf};
+
+use animeku_cli::{models::Episode, t};
+use chrono::{DateTime, SecondsFormat};
+use html_escape::{encode_double_quoted_attribute, encode_text};
+use serde::{Deserialize, Serialize};
+
+use crate::{config, watchlist::Entry};
+
+static MAX_ITEMS: usize = 200;
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Item {
+    pub source: String,
+    pub show_id: String,
+    pub show_title: String,
+    pub thumb_url: Option<String>,
+    pub episode: Episode,
+    pub found: u64,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Feed {
+    pub items: Vec<Item>,
+}
+
+pub fn path() -> PathBuf {
+    config::data_dir().join("feed.json")
+}
+
+fn timestamp(secs: u64) -> String {
+    DateTime::from_timestamp(secs as i64, 0)
+        .unwrap_or_default()
+        .to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+impl Feed {
+    pub fn load() -> Self {
+        fs::read(path())
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        let path = path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn push(&mut self, entry: &Entry, episodes: &[Episode], found: u64) {
+        for episode in episodes {
+            self.items.insert(
+                0,
+                Item {
+                    source: entry.source.clone(),
+                    show_id: entry.id.clone(),
+                    show_title: entry.title.clone(),
+                    thumb_url: entry.thumb_url.clone(),
+                    episode: episode.clone(),
+                    found,
+                },
+            );
+        }
+        self.items.truncate(MAX_ITEMS);
+    }
+
+    /// Renders the feed as Atom, `link` builds the play url of each item.
+    pub fn atom(&self, self_url: &str, link: impl Fn(&Item) -> String) -> String {
+        let updated = self.items.iter().map(|i| i.found).max().unwrap_or_default();
+        let mut out = format!(
+            concat!(
+                "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n",
+                "<feed xmlns=\"http://www.w3.org/2005/Atom\" ",
+                "xmlns:media=\"http://search.yahoo.com/mrss/\">\n",
+                "  <id>{}</id>\n",
+                "  <title>{}</title>\n",
+                "  <updated>{}</updated>\n",
+                "  <link rel=\"self\" href=\"{}\"/>\n",
+                "  <generator>{} {}</generator>\n"
+            ),
+            encode_text(self_url),
+            encode_text(&t!("feed.title", env!("CARGO_PKG_NAME"))),
+            timestamp(updated),
+            encode_double_quoted_attribute(self_url),
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_VERSION"),
+        );
+
+        for item in &self.items {
+            let title = format!("{} - {}", item.show_title.trim(), item.episode.title.trim());
+            let link = link(item);
+            out.push_str("  <entry>\n");
+            out.push_str(&format!(
+                "    <id>tag:{},{}:{}/{}</id>\n",
+                env!("CARGO_PKG_NAME"),
+                timestamp(item.found).get(..10).unwrap_or_default(),
+                encode_text(&item.source),
+                encode_text(&item.episode.id)
+            ));
+            out.push_str(&format!("    <title>{}</title>\n", encode_text(&title)));
+            out.push_str(&format!(
+                "    <updated>{}</updated>\n",
+                timestamp(item.found)
+            ));
+            out.push_str(&format!(
+                "    <author><name>{}</name></author>\n",
+                encode_text(&item.source)
+            ));
+            out.push_str(&format!(
+                "    <category term=\"{}\"/>\n",
+                encode_double_quoted_attribute(item.show_title.trim())
+            ));
+            out.push_str(&format!(
+                "    <link rel=\"alternate\" href=\"{}\"/>\n",
+                encode_double_quoted_attribute(&link)
+            ));
+            out.push_str(&format!("    <summary>{}</summary>\n", encode_text(&title)));
+            if let Some(ref thumb) = item.thumb_url {
+                out.push_str(&format!(
+                    "    <media:thumbnail url=\"{}\"/>\n",
+                    encode_double_quoted_attribute(thumb)
+                ));
+                out.push_str(&format!(
+                    "    <content type=\"html\">{}</content>\n",
+                    encode_text(&format!(
+                        "<img src=\"{}\" alt=\"\"/><p><a href=\"{}\">{}</a></p>",
+                        encode_double_quoted_attribute(thumb),
+                        encode_double_quoted_attribute(&link),
+                        encode_text(&title)
+                    ))
+                ));
+            }
+            out.push_str("  </entry>\n");
+        }
+        out.push_str("</feed>\n");
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(id: &str, title: &str, thumb_url: Option<&str>, found: u64) -> Item {
+        Item {
+            source: "anime".into(),
+            show_id: "show".into(),
+            show_title: title.into(),
+            thumb_url: thumb_url.map(String::from),
+            episode: Episode {
+                id: id.into(),
+                title: format!("Episode {}", id),
+                is_series: true,
+            },
+            found,
+        }
+    }
+
+    #[test]
+    fn atom_escapes_and_dates_entries() {
+        let feed = Feed {
+            items: vec![
+                item(
+                    "2",
+                    "Tom & Jerry <Classic>",
+                    Some("http://x/a.jpg?w=1&h=2"),
+                    1_700_086_400,
+                ),
+                item("1", "Plain", None, 1_700_000_000),
+            ],
+        };
+        let atom = feed.atom("http://host/feed.xml?token=a&b", |i| {
+            format!("http://host/play/{}?x=\"1\"", i.episode.id)
+        });
+
+        assert!(atom.contains("<updated>2023-11-15T22:13:20Z</updated>\n  <link rel=\"self\""));
+        assert!(atom.contains("<id>http://host/feed.xml?token=a&amp;b</id>"));
+        assert!(atom.contains("<id>tag:animeku-cli,2023-11-15:anime/2</id>"));
+        assert!(atom.contains("<id>tag:animeku-cli,2023-11-14:anime/1</id>"));
+        assert!(atom.contains("<title>Tom &amp; Jerry &lt;Classic&gt; - Episode 2</title>"));
+        assert!(atom.contains("href=\"http://host/play/2?x=&quot;1&quot;\""));
+        assert!(atom.contains("url=\"http://x/a.jpg?w=1&amp;h=2\""));
+        assert_eq!(atom.matches("<summary>").count(), 2);
+        assert!(atom.contains("<summary>Plain - Episode 1</summary>"));
+        assert_eq!(atom.matches("<content").count(), 1);
+        assert!(atom.find("anime/2").unwrap() < atom.find("anime/1").unwrap());
+    }
+
+    #[test]
+    fn newest_items_come_first() {
+        let entry = Entry {
+            source: "anime".into(),
+            id: "show".into(),
+            title: "Show".into(),
+            ..Default::default()
+        };
+        let mut feed = Feed::default();
+        feed.push(&entry, &[item("1", "", None, 0).episode], 10);
+        feed.push(&entry, &[item("2", "", None, 0).episode], 20);
+        let found = feed.items.iter().map(|i| i.found).collect::<Vec<_>>();
+        assert_eq!(found, [20, 10]);
+    }
+}
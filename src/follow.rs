@@ -0,0 +1,152 @@
+use animeku_cli::{
+    ext::source_index,
+    models::{Episode, Meta},
+    pipeline::Pipeline,
+};
+use futures::{stream, StreamExt};
+
+use crate::{
+    config,
+    download::Queue,
+    feed::Feed,
+    watchlist::{now, ongoing, Entry, Watchlist},
+};
+
+static MAX_RELEASES: usize = 12;
+
+pub struct Check {
+    pub entry: Entry,
+    pub result: anyhow::Result<Vec<Episode>>,
+}
+
+async fn fetch(entry: &Entry) -> anyhow::Result<(Vec<Episode>, Meta)> {
+    Pipeline::for_source(source_index(&entry.source)?, &config::get().ext())
+        .episodes(entry.movie())
+        .await
+}
+
+/// Stores a check of `entry` made at `at`, returning the episodes not seen
+/// before. The first check only takes the current episodes as seen.
+fn record(entry: &mut Entry, episodes: &[Episode], ongoing: bool, at: u64) -> Vec<Episode> {
+    let first_check = entry.seen_ids.is_empty();
+    let new = episodes
+        .iter()
+        .filter(|e| !entry.seen_ids.contains(&e.id))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    entry.checked = at;
+    entry.ongoing = ongoing;
+    entry.episodes = episodes.len();
+    if !new.is_empty() {
+        entry.seen_ids.extend(new.iter().map(|e| e.id.clone()));
+        entry.updated = at;
+        if !first_check {
+            entry.releases.push(at);
+            let excess = entry.releases.len().saturating_sub(MAX_RELEASES);
+            entry.releases.drain(..excess);
+        }
+    }
+    if first_check {
+        Vec::new()
+    } else {
+        new
+    }
+}
+
+/// Checks followed entries last checked at least `min_age` seconds ago.
+pub async fn check(min_age: u64) -> anyhow::Result<Vec<Check>> {
+    let followed = Watchlist::load()
+        .entries
+        .into_iter()
+        .filter(|e| e.followed && now().saturating_sub(e.checked) >= min_age)
+        .collect::<Vec<_>>();
+
+    let fetched = stream::iter(followed)
+        .map(|entry| async move {
+            let result = fetch(&entry).await;
+            (entry, result)
+        })
+        .buffer_unordered(4)
+        .collect::<Vec<_>>()
+        .await;
+
+    let mut watchlist = Watchlist::load();
+    let mut feed = Feed::load();
+    let mut queue = Queue::load();
+    let auto_download = config::get().follow.download;
+    let mut checks = Vec::new();
+    for (entry, result) in fetched {
+        let result =
+            result.map(
+                |(episodes, meta)| match watchlist.get_mut(&entry.source, &entry.id) {
+                    Some(stored) => record(stored, &episodes, ongoing(&meta), now()),
+                    None => Vec::new(),
+                },
+            );
+        if let Ok(ref new) = result {
+            feed.push(&entry, new, now());
+            if auto_download {
+                for episode in new {
+                    queue.push(&entry.source, &entry.title, episode);
+                }
+            }
+        }
+        checks.push(Check { entry, result });
+    }
+    watchlist.save()?;
+    feed.save()?;
+    queue.save()?;
+    Ok(checks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn episodes(ids: &[&str]) -> Vec<Episode> {
+        ids.iter()
+            .map(|id| Episode {
+                id: id.to_string(),
+                title: id.to_string(),
+                is_series: true,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn reports_only_unseen_episodes() {
+        let mut entry = Entry {
+            followed: true,
+            ..Default::default()
+        };
+        assert!(record(&mut entry, &episodes(&["1", "2"]), true, 10).is_empty());
+        assert!(entry.releases.is_empty());
+
+        let new = record(&mut entry, &episodes(&["1", "2", "3"]), true, 20);
+        assert_eq!(new.len(), 1);
+        assert_eq!(new[0].id, "3");
+        assert_eq!(entry.releases, [20]);
+        assert_eq!((entry.episodes, entry.checked), (3, 20));
+
+        assert!(record(&mut entry, &episodes(&["1", "2", "3"]), false, 30).is_empty());
+        assert_eq!(entry.releases, [20]);
+        assert!(!entry.ongoing);
+    }
+
+    #[test]
+    fn refollow_starts_from_current_episodes() {
+        let mut watchlist = Watchlist::default();
+        let entry = Entry {
+            source: "anime".into(),
+            id: "a".into(),
+            ..Default::default()
+        };
+        watchlist.follow(entry.clone(), vec!["1".into()]);
+        watchlist.unfollow("anime", "a");
+        watchlist.follow(entry, vec![]);
+
+        let stored = watchlist.get_mut("anime", "a").unwrap();
+        assert!(record(stored, &episodes(&["1", "2", "3"]), true, 10).is_empty());
+    }
+}
@@ -0,0 +1,108 @@
+use std::{fs, path::PathBuf};
+
+use animeku_cli::{
+    models::{Episode, Movie},
+    util::episode_number,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config,
+    watchlist::{now, Watchlist},
+};
+
+static MAX_ENTRIES: usize = 2000;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Watched {
+    pub source: String,
+    pub show_id: String,
+    pub show_title: String,
+    pub episode_id: String,
+    pub episode_title: String,
+    pub episode: Option<usize>,
+    pub watched: u64,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct History {
+    pub entries: Vec<Watched>,
+}
+
+pub fn path() -> PathBuf {
+    config::data_dir().join("history.json")
+}
+
+impl History {
+    pub fn load() -> Self {
+        fs::read(path())
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        let path = path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Union of both histories, oldest first.
+    pub fn merge(&mut self, other: History) {
+        for watched in other.entries {
+            if !self.entries.contains(&watched) {
+                self.entries.push(watched);
+            }
+        }
+        self.entries.sort_by_key(|w| w.watched);
+        let excess = self.entries.len().saturating_sub(MAX_ENTRIES);
+        self.entries.drain(..excess);
+    }
+
+    /// Highest episode number watched for a show.
+    pub fn progress(&self, source: &str, show_id: &str) -> usize {
+        self.entries
+            .iter()
+            .filter(|w| w.source == source && w.show_id == show_id)
+            .filter_map(|w| w.episode)
+            .max()
+            .unwrap_or_default()
+    }
+}
+
+/// Records a finished episode and moves the watchlist progress forward.
+pub fn record(source: &str, movie: &Movie, episode: &Episode) -> anyhow::Result<()> {
+    let number = match episode_number(&episode.title) {
+        Some(number) => Some(number),
+        None if !episode.is_series => Some(1),
+        None => None,
+    };
+
+    let mut history = History::load();
+    history.entries.push(Watched {
+        source: source.into(),
+        show_id: movie.id.clone(),
+        show_title: movie.title.trim().into(),
+        episode_id: episode.id.clone(),
+        episode_title: episode.title.trim().into(),
+        episode: number,
+        watched: now(),
+    });
+    let excess = history.entries.len().saturating_sub(MAX_ENTRIES);
+    history.entries.drain(..excess);
+    history.save()?;
+
+    let mut watchlist = Watchlist::load();
+    if let (Some(entry), Some(number)) = (watchlist.get_mut(source, &movie.id), number) {
+        if number > entry.progress {
+            entry.progress = number;
+            watchlist.save()?;
+        }
+    }
+    Ok(())
+}
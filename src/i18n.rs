@@ -0,0 +1,615 @@
+use std::{collections::HashMap, env, fmt::Display, sync::RwLock};
+
+use once_cell::sync::Lazy;
+
+static LANG: RwLock<Option<Lang>> = RwLock::new(None);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lang {
+    Id,
+    En,
+}
+
+impl Lang {
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.to_lowercase();
+        if value.starts_with("id") || value.starts_with("in") {
+            Some(Lang::Id)
+        } else if value.starts_with("en") {
+            Some(Lang::En)
+        } else {
+            None
+        }
+    }
+}
+
+#[macro_export]
+macro_rules! t {
+    ($key:literal $(,)?) => {
+        $crate::i18n::tr($key)
+    };
+    ($key:literal, $($arg:expr),+ $(,)?) => {
+        $crate::i18n::format($crate::i18n::tr($key), &[$(&$arg as &dyn std::fmt::Display),+])
+    };
+}
+
+static MESSAGES: &[(&str, &str, &str)] = &[
+    ("input.title", "Masukan judul", "Enter a title"),
+    ("input.menu", "Menu", "Menu"),
+    ("input.menu.search", "Cari judul", "Search a title"),
+    ("input.menu.favorites", "Favorit ({})", "Favorites ({})"),
+    ("input.source", "Tipe pencarian", "Search type"),
+    (
+        "input.source.anime",
+        "Anime on-going dan complete",
+        "Ongoing and completed anime",
+    ),
+    (
+        "input.source.movie",
+        "Film asia dan anime live action",
+        "Asian films and live action anime",
+    ),
+    (
+        "input.source.tenflix",
+        "Film Box Office dan TV Series",
+        "Box office films and TV series",
+    ),
+    ("input.choose", "Pilih", "Choose"),
+    (
+        "input.choose_many",
+        "Pilih (spasi untuk menandai, enter untuk lanjut)",
+        "Choose (space to mark, enter to continue)",
+    ),
+    (
+        "source.unknown",
+        "sumber '{}' tidak dikenal, pilih salah satu dari: {}",
+        "unknown source '{}', choose one of: {}",
+    ),
+    (
+        "search.progress",
+        "Proses pencarian '{}'.. ",
+        "Searching '{}'.. ",
+    ),
+    ("search.found", "ditemukan {} judul", "found {} titles"),
+    ("search.not_found", "tidak ditemukan!", "nothing found!"),
+    (
+        "search.check_keyword",
+        "Silahkan periksa kembali kata kunci pencarian",
+        "Please check your search keywords",
+    ),
+    (
+        "search.prev_page",
+        "Sebelumnnya (Halaman {})",
+        "Previous (Page {})",
+    ),
+    (
+        "search.next_page",
+        "Selanjutnya (Halaman {})",
+        "Next (Page {})",
+    ),
+    (
+        "episode.progress",
+        "Memuat daftar episode '{}' .. ",
+        "Loading episode list '{}' .. ",
+    ),
+    (
+        "stream.progress",
+        "Memuat tautan unduhan '{}' .. ",
+        "Loading download links '{}' .. ",
+    ),
+    (
+        "stream.restart",
+        "Silahkan jalankan ulang program",
+        "Please restart the program",
+    ),
+    ("status.success", "berhasil", "done"),
+    ("status.unsuccessful", "tidak berhasil!", "unsuccessful!"),
+    ("status.failed", "gagal", "failed"),
+    ("status.failed_excl", "gagal!", "failed!"),
+    ("details.title", " Deskripsi ", " Details "),
+    ("preview.open", "Buka judul ini", "Open this title"),
+    (
+        "preview.back",
+        "Kembali ke hasil pencarian",
+        "Back to the results",
+    ),
+    (
+        "tui.help",
+        "Tab pindah panel · Enter pilih · n/p halaman · f favorit · Ctrl+S ganti sumber · q keluar",
+        "Tab switch pane · Enter select · n/p page · f favorite · Ctrl+S change source · q quit",
+    ),
+    ("tui.results", "Hasil", "Results"),
+    ("tui.page", "Halaman {}", "Page {}"),
+    ("web.search", "Cari", "Search"),
+    ("web.back", "← Kembali", "← Back"),
+    (
+        "web.no_streams",
+        "Tautan tidak ditemukan",
+        "No streams found",
+    ),
+    (
+        "web.hls_failed",
+        "Gagal memuat hls.js, coba buka tautan di pemutar lain",
+        "Could not load hls.js, try opening the link in another player",
+    ),
+    ("tui.episodes", "Episode", "Episodes"),
+    ("tui.streams", "Tautan", "Streams"),
+    (
+        "tui.last_page",
+        "Sudah di halaman terakhir",
+        "Already on the last page",
+    ),
+    (
+        "player.opening",
+        "Membuka tautan diaplikasi eksternal .. ",
+        "Opening link in an external application .. ",
+    ),
+    ("player.choose", "Buka dengan", "Open with"),
+    ("player.open_in", "Putar di {}", "Play in {}"),
+    (
+        "player.open_default",
+        "Buka di aplikasi bawaan",
+        "Open in the default application",
+    ),
+    ("player.failed", "Gagal menjalankan {}", "Failed to run {}"),
+    (
+        "player.watched",
+        "Tandai episode ini sudah ditonton",
+        "Mark this episode as watched",
+    ),
+    (
+        "player.quit",
+        "Apakah kamu ingin keluar",
+        "Do you want to quit",
+    ),
+    (
+        "config.missing_value",
+        "nilai untuk '{}' belum diisi",
+        "missing value for '{}'",
+    ),
+    (
+        "config.key_not_found",
+        "kunci '{}' tidak ditemukan",
+        "key '{}' not found",
+    ),
+    (
+        "config.empty_key",
+        "kunci tidak boleh kosong",
+        "key must not be empty",
+    ),
+    (
+        "config.invalid",
+        "konfigurasi tidak valid",
+        "invalid configuration",
+    ),
+    (
+        "config.not_table",
+        "'{}' bukan sebuah tabel",
+        "'{}' is not a table",
+    ),
+    (
+        "config.invalid_value",
+        "nilai untuk '{}' tidak valid: {}",
+        "invalid value for '{}': {}",
+    ),
+    ("config.removed", "{} dihapus", "{} removed"),
+    ("favorite.add", "★ Simpan ke favorit", "★ Add to favorites"),
+    (
+        "favorite.remove",
+        "☆ Hapus dari favorit",
+        "☆ Remove from favorites",
+    ),
+    (
+        "favorite.added",
+        "{} disimpan ke favorit",
+        "{} added to favorites",
+    ),
+    (
+        "favorite.removed",
+        "{} dihapus dari favorit",
+        "{} removed from favorites",
+    ),
+    ("favorite.open", "Buka", "Open"),
+    ("favorite.tags", "Ubah tag", "Edit tags"),
+    ("favorite.delete", "Hapus", "Remove"),
+    ("favorite.back", "Kembali", "Back"),
+    ("favorite.sort", "Urutkan: {}", "Sort: {}"),
+    (
+        "favorite.sort.added",
+        "terbaru ditambahkan",
+        "recently added",
+    ),
+    (
+        "favorite.sort.updated",
+        "terakhir diperbarui",
+        "recently updated",
+    ),
+    ("favorite.sort.title", "judul", "title"),
+    ("favorite.sort.source", "sumber", "source"),
+    (
+        "favorite.new_tags",
+        "Tag lain (pisahkan dengan koma)",
+        "Other tags (comma separated)",
+    ),
+    (
+        "follow.add",
+        "🔔 Ikuti episode baru",
+        "🔔 Follow new episodes",
+    ),
+    ("follow.remove", "🔕 Berhenti mengikuti", "🔕 Unfollow"),
+    (
+        "follow.added",
+        "{} diikuti, episode baru akan diperiksa berkala",
+        "Following {}, new episodes will be checked periodically",
+    ),
+    (
+        "follow.removed",
+        "{} tidak diikuti lagi",
+        "{} is no longer followed",
+    ),
+    (
+        "check.progress",
+        "Memeriksa {} judul yang diikuti .. ",
+        "Checking {} followed titles .. ",
+    ),
+    (
+        "check.empty",
+        "Belum ada judul yang diikuti",
+        "No followed titles yet",
+    ),
+    ("check.new", "{}: {} episode baru", "{}: {} new episodes"),
+    ("check.none", "Tidak ada episode baru", "No new episodes"),
+    (
+        "check.failed",
+        "{} gagal diperiksa: {}",
+        "Failed to check {}: {}",
+    ),
+    (
+        "check.downloading",
+        "Mengunduh {} .. ",
+        "Downloading {} .. ",
+    ),
+    (
+        "check.next",
+        "Pemeriksaan berikutnya dalam {} menit",
+        "Next check in {} minutes",
+    ),
+    ("check.notify_title", "Episode baru: {}", "New episode: {}"),
+    ("anilist.error", "AniList: {}", "AniList: {}"),
+    (
+        "anilist.not_found",
+        "{} tidak ditemukan di AniList",
+        "{} was not found on AniList",
+    ),
+    (
+        "anilist.confirm",
+        "Pilih judul AniList untuk {}",
+        "Choose the AniList title for {}",
+    ),
+    (
+        "anilist.ignore",
+        "Jangan sinkronkan judul ini",
+        "Do not sync this title",
+    ),
+    (
+        "anilist.saved",
+        "Progres {} di AniList: episode {}",
+        "AniList progress for {}: episode {}",
+    ),
+    (
+        "history.exported",
+        "{} judul disimpan di {}",
+        "{} titles saved to {}",
+    ),
+    (
+        "history.importing",
+        "Mencocokkan {} judul dengan nontonanime ..",
+        "Matching {} titles with nontonanime ..",
+    ),
+    (
+        "history.imported",
+        "{} judul diimpor, {} judul menunggu dicocokkan lewat `history review`",
+        "{} titles imported, {} titles waiting in `history review`",
+    ),
+    (
+        "history.queued",
+        "{} masuk antrean: {}",
+        "{} was queued for review: {}",
+    ),
+    (
+        "history.review.prompt",
+        "{} (ditonton {} episode)",
+        "{} ({} episodes watched)",
+    ),
+    (
+        "history.review.search",
+        "Cari dengan judul lain",
+        "Search another title",
+    ),
+    ("history.review.skip", "Lewati dulu", "Skip for now"),
+    (
+        "history.review.drop",
+        "Hapus dari antrean",
+        "Remove from the queue",
+    ),
+    (
+        "history.review.empty",
+        "Tidak ada judul yang perlu dicocokkan",
+        "Nothing left to review",
+    ),
+    (
+        "sync.progress",
+        "Menyinkronkan riwayat dan favorit .. ",
+        "Syncing history and favorites .. ",
+    ),
+    (
+        "sync.not_configured",
+        "isi sync.dir atau sync.webdav di file konfigurasi",
+        "set sync.dir or sync.webdav in the config file",
+    ),
+    ("sync.failed", "{} gagal: {}", "{} failed: {}"),
+    (
+        "sync.conflict",
+        "{} terus berubah di remote, coba sinkron lagi nanti",
+        "{} keeps changing on the remote, try syncing again later",
+    ),
+    ("profile.choose", "Pilih profil", "Choose a profile"),
+    ("profile.pin", "PIN profil {}", "PIN for profile {}"),
+    ("profile.new_pin", "PIN baru", "New PIN"),
+    ("profile.pin_confirm", "Ulangi PIN", "Repeat the PIN"),
+    (
+        "profile.pin_mismatch",
+        "PIN tidak sama",
+        "The PINs do not match",
+    ),
+    ("profile.wrong_pin", "PIN salah", "Wrong PIN"),
+    (
+        "profile.pin_required",
+        "{} dikunci dengan PIN, jalankan dari terminal",
+        "{} is locked with a PIN, run it from a terminal",
+    ),
+    (
+        "profile.locked",
+        "terlalu banyak percobaan PIN untuk {}",
+        "too many PIN attempts for {}",
+    ),
+    ("profile.label", "profil {}", "profile {}"),
+    (
+        "profile.unknown",
+        "profil '{}' tidak ditemukan, buat dengan `profile add`",
+        "profile '{}' not found, create it with `profile add`",
+    ),
+    (
+        "profile.invalid_name",
+        "nama profil '{}' hanya boleh berisi huruf, angka, - dan _",
+        "profile name '{}' may only contain letters, digits, - and _",
+    ),
+    (
+        "profile.exists",
+        "profil '{}' sudah ada",
+        "profile '{}' already exists",
+    ),
+    ("profile.added", "Profil {} dibuat", "Profile {} created"),
+    (
+        "profile.pin_saved",
+        "PIN profil {} disimpan",
+        "PIN for profile {} saved",
+    ),
+    (
+        "profile.confirm_remove",
+        "Hapus profil {} beserta riwayat dan favoritnya?",
+        "Remove profile {} with its history and favorites?",
+    ),
+    ("profile.removed", "Profil {} dihapus", "Profile {} removed"),
+    (
+        "filter.blocked",
+        "{} disembunyikan oleh filter konten",
+        "{} is hidden by the content filter",
+    ),
+    ("filter.label", "filter konten", "content filter"),
+    (
+        "filter.pin_first",
+        "pasang filter.pin sebelum mewajibkan filter",
+        "set filter.pin before making the filter mandatory",
+    ),
+    ("filter.pin", "PIN filter konten", "Content filter PIN"),
+    (
+        "filter.new_pin",
+        "PIN filter konten baru",
+        "New content filter PIN",
+    ),
+    (
+        "filter.pin_argument",
+        "jangan tulis PIN di baris perintah, jalankan config set filter.pin tanpa nilai",
+        "do not pass the PIN on the command line, run config set filter.pin without a value",
+    ),
+    (
+        "filter.pin_saved",
+        "PIN filter konten disimpan",
+        "Content filter PIN saved",
+    ),
+    ("calendar.name", "Jadwal anime", "Anime schedule"),
+    (
+        "calendar.description",
+        "Perkiraan episode {} dari {}",
+        "Expected episode {} from {}",
+    ),
+    (
+        "calendar.saved",
+        "Kalender disimpan di {} ({} judul)",
+        "Calendar saved to {} ({} titles)",
+    ),
+    ("feed.title", "{}: episode baru", "{}: new episodes"),
+    ("feed.saved", "Feed disimpan di {}", "Feed saved to {}"),
+    (
+        "export.progress",
+        "Mengambil tautan {} episode .. ",
+        "Resolving links for {} episodes .. ",
+    ),
+    ("export.skipped", "{} dilewati: {}", "{} skipped: {}"),
+    (
+        "export.no_stream",
+        "tautan tidak ditemukan",
+        "no link found",
+    ),
+    (
+        "export.saved",
+        "Playlist disimpan di {}",
+        "Playlist saved to {}",
+    ),
+    ("cast.option", "Cast ke TV (DLNA)", "Cast to TV (DLNA)"),
+    (
+        "cast.searching",
+        "Mencari TV di jaringan .. ",
+        "Looking for TVs on the network .. ",
+    ),
+    (
+        "cast.not_found",
+        "Tidak ada TV yang ditemukan",
+        "No TV was found",
+    ),
+    ("cast.choose", "Pilih TV", "Choose a TV"),
+    ("cast.playing", "Memutar di {}", "Playing on {}"),
+    ("cast.pause", "Jeda", "Pause"),
+    ("cast.resume", "Lanjutkan", "Resume"),
+    ("cast.seek", "Lompat ke waktu", "Seek"),
+    (
+        "cast.seek_prompt",
+        "Waktu (jj:mm:dd atau detik)",
+        "Time (hh:mm:ss or seconds)",
+    ),
+    ("cast.stop", "Berhenti", "Stop"),
+    (
+        "cast.invalid_time",
+        "format waktu tidak valid",
+        "invalid time format",
+    ),
+    (
+        "cast.no_transport",
+        "{} tidak mendukung AVTransport",
+        "{} does not support AVTransport",
+    ),
+    ("cast.failed", "{} gagal: {}", "{} failed: {}"),
+    (
+        "server.listening",
+        "Server berjalan di http://{}",
+        "Server listening on http://{}",
+    ),
+    ("server.unauthorized", "token tidak valid", "invalid token"),
+    (
+        "server.token_required",
+        "Server di {} terbuka untuk jaringan, pasang token dengan --token",
+        "The server on {} is reachable from the network, set a token with --token",
+    ),
+    ("server.not_found", "data tidak ditemukan", "not found"),
+    (
+        "mirror.unavailable",
+        "tidak ada mirror yang tersedia",
+        "no mirror is available",
+    ),
+    (
+        "mirror.parked",
+        "domain {} sudah tidak aktif",
+        "domain {} is no longer active",
+    ),
+    (
+        "mirror.status",
+        "mirror {} membalas dengan status {}",
+        "mirror {} responded with status {}",
+    ),
+    (
+        "doh.not_found",
+        "DoH tidak menemukan alamat untuk {}",
+        "DoH found no address for {}",
+    ),
+    (
+        "metadata.failed",
+        "Detail tambahan {} gagal diambil: {}",
+        "Could not fetch extra details of {}: {}",
+    ),
+];
+
+static META_KEYS: &[(&str, &str)] = &[
+    ("Judul", "Title"),
+    ("Genre", "Genre"),
+    ("Tahun", "Year"),
+    ("Rating", "Rating"),
+    ("On-Going", "On-going"),
+    ("Bahasa", "Language"),
+    ("Episode", "Episodes"),
+    ("Durasi", "Duration"),
+    ("Negara", "Country"),
+    ("Sutradara", "Director"),
+    ("Pemeran", "Cast"),
+    ("Kualitas", "Quality"),
+    ("Rilis", "Released"),
+    ("Status", "Status"),
+    ("Studio", "Studio"),
+    ("Judul Inggris", "English title"),
+    ("Judul Jepang", "Japanese title"),
+    ("Skor", "Score"),
+    ("Sinopsis", "Synopsis"),
+];
+
+static CATALOG: Lazy<HashMap<&'static str, (&'static str, &'static str)>> = Lazy::new(|| {
+    MESSAGES
+        .iter()
+        .map(|(key, id, en)| (*key, (*id, *en)))
+        .collect()
+});
+
+/// Picks the language from a config value, falling back to the locale
+/// environment variables.
+pub fn set_lang(language: Option<&str>) {
+    *LANG.write().unwrap() = language.and_then(Lang::parse).or_else(env_lang);
+}
+
+fn env_lang() -> Option<Lang> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|name| env::var(name).ok())
+        .find_map(|value| Lang::parse(&value))
+}
+
+pub fn lang() -> Lang {
+    if let Some(lang) = *LANG.read().unwrap() {
+        return lang;
+    }
+    env_lang().unwrap_or(Lang::Id)
+}
+
+pub fn tr(key: &'static str) -> &'static str {
+    match CATALOG.get(key) {
+        Some((id, en)) => match lang() {
+            Lang::Id => id,
+            Lang::En => en,
+        },
+        None => key,
+    }
+}
+
+pub fn format(template: &str, args: &[&dyn Display]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut args = args.iter();
+    let mut parts = template.split("{}").peekable();
+    while let Some(part) = parts.next() {
+        out.push_str(part);
+        if parts.peek().is_some() {
+            if let Some(arg) = args.next() {
+                out.push_str(&arg.to_string());
+            }
+        }
+    }
+    out
+}
+
+pub fn meta_key(key: &str) -> String {
+    let found = META_KEYS
+        .iter()
+        .find(|(id, en)| key.eq_ignore_ascii_case(id) || key.eq_ignore_ascii_case(en));
+    match (found, lang()) {
+        (Some((id, _)), Lang::Id) => id.to_string(),
+        (Some((_, en)), Lang::En) => en.to_string(),
+        (None, _) => key.to_string(),
+    }
+}
@@ -1,34 +1,75 @@
 use dialoguer::theme::ColorfulTheme;
 
-use crate::models::Input;
+use animeku_cli::{ext::source_index, models::Movie, t};
 
-pub fn get_user_input() -> anyhow::Result<Input> {
-    let title: String = dialoguer::Input::with_theme(&ColorfulTheme::default())
-        .with_prompt("Masukan judul")
-        .interact()?;
+use crate::{favorites, watchlist::Watchlist};
 
-    let tipe = dialoguer::Select::with_theme(&ColorfulTheme::default())
-        .with_prompt("Tipe pencarian")
-        .default(0)
-        .item("Anime on-going dan complete")
-        .item("Film asia dan anime live action")
-        .item("Film Box Office dan TV Series")
-        .interact()?;
+pub struct Input {
+    pub title: String,
+    pub tipe: usize,
+    pub movie: Option<Movie>,
+}
 
-    Ok(Input { title, tipe })
+pub fn get_user_input(title: Option<String>, tipe: Option<usize>) -> anyhow::Result<Input> {
+    let title = match title {
+        Some(title) => title,
+        None => loop {
+            let favorites = Watchlist::load().entries.len();
+            if favorites > 0
+                && dialoguer::Select::with_theme(&ColorfulTheme::default())
+                    .with_prompt(t!("input.menu"))
+                    .default(0)
+                    .item(t!("input.menu.search"))
+                    .item(t!("input.menu.favorites", favorites))
+                    .interact()?
+                    == 1
+            {
+                match favorites::menu()? {
+                    Some(entry) => {
+                        return Ok(Input {
+                            title: entry.title.clone(),
+                            tipe: source_index(&entry.source)?,
+                            movie: Some(entry.movie()),
+                        })
+                    }
+                    None => continue,
+                }
+            }
+            break dialoguer::Input::with_theme(&ColorfulTheme::default())
+                .with_prompt(t!("input.title"))
+                .interact()?;
+        },
+    };
+
+    let tipe = match tipe {
+        Some(tipe) => tipe,
+        None => dialoguer::Select::with_theme(&ColorfulTheme::default())
+            .with_prompt(t!("input.source"))
+            .default(0)
+            .item(t!("input.source.anime"))
+            .item(t!("input.source.movie"))
+            .item(t!("input.source.tenflix"))
+            .interact()?,
+    };
+
+    Ok(Input {
+        title,
+        tipe,
+        movie: None,
+    })
 }
 
 pub fn choice<T: std::fmt::Display + Clone>(options: Vec<T>, fuzzy: bool) -> anyhow::Result<T> {
     let selected = if !fuzzy {
         dialoguer::Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("Pilih")
+            .with_prompt(t!("input.choose"))
             .default(0)
             .max_length(5)
             .items(&options)
             .interact()?
     } else {
         dialoguer::FuzzySelect::with_theme(&ColorfulTheme::default())
-            .with_prompt("Pilih")
+            .with_prompt(t!("input.choose"))
             .default(0)
             .max_length(5)
             .items(&options)
@@ -36,3 +77,12 @@ pub fn choice<T: std::fmt::Display + Clone>(options: Vec<T>, fuzzy: bool) -> any
     };
     Ok(options[selected].clone())
 }
+
+pub fn multi_choice<T: std::fmt::Display + Clone>(options: Vec<T>) -> anyhow::Result<Vec<T>> {
+    let selected = dialoguer::MultiSelect::with_theme(&ColorfulTheme::default())
+        .with_prompt(t!("input.choose_many"))
+        .max_length(10)
+        .items(&options)
+        .interact()?;
+    Ok(selected.into_iter().map(|i| options[i].clone()).collect())
+}
@@ -0,0 +1,19 @@
+//! Extractor anime dan film yang dipakai oleh `animeku-cli`.
+//!
+//! ```no_run
+//! use animeku_cli::{ext::config::ExtConfig, pipeline::Pipeline};
+//!
+//! # async fn run() -> anyhow::Result<()> {
+//! let pipeline = Pipeline::from_source("anime", &ExtConfig::default())?;
+//! let (movies, _) = pipeline.search("one piece", 1).await?;
+//! let (episodes, _meta) = pipeline.episodes(movies[0].clone()).await?;
+//! let stream = pipeline.resolve(episodes[0].clone(), Some("720p")).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod ext;
+pub mod i18n;
+pub mod models;
+pub mod pipeline;
+pub mod util;
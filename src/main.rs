@@ -1,95 +1,214 @@
 use std::io::{stdout, Write};
 use std::process::Command;
 
-use animeku::AnimekuCli;
+use animeku::{clearscreen_and_show_banner, AnimekuCli};
+use animeku_cli::{ext::source_index, i18n, t};
+use args::{Args, Command as SubCommand};
+use clap::Parser;
 use colored::Colorize;
 use dialoguer::theme::ColorfulTheme;
-use ext::{nontonanime, Ext};
 use tokio::runtime;
 
-use crate::{ext::tenflix, input::get_user_input, util::clearscreen_and_show_banner};
+use crate::input::get_user_input;
 
+mod anilist;
 mod animeku;
-mod ext;
+mod args;
+mod calendar;
+mod cast;
+mod commands;
+mod config;
+mod download;
+mod favorites;
+mod feed;
+mod follow;
+mod history;
 mod input;
-mod models;
-mod util;
-
-fn get_ext(id: usize) -> Box<dyn Ext> {
-    if id == 0 {
-        Box::new(nontonanime::anime::AnimeExt::new())
-    } else if id == 1 {
-        Box::new(nontonanime::movie::MovieExt::new())
-    } else {
-        Box::new(tenflix::TenflixExt::new())
+mod mal;
+mod metadata;
+mod profile;
+mod progress;
+mod resume;
+mod server;
+mod sync;
+mod thumbnail;
+mod tui;
+mod watchlist;
+
+fn configured_source() -> anyhow::Result<Option<usize>> {
+    match config::get().source {
+        Some(ref source) => Ok(Some(source_index(source)?)),
+        None => Ok(None),
     }
 }
 
 #[allow(unreachable_code)]
-async fn app() -> anyhow::Result<()> {
-    clearscreen_and_show_banner()?;
+async fn app(title: Option<String>) -> anyhow::Result<()> {
+    let config = config::get();
+    let tipe = configured_source()?;
 
-    let input = get_user_input()?;
-    let extractor = get_ext(input.tipe);
+    if config.tui {
+        return tui::run(tipe.unwrap_or(0), title).await;
+    }
 
-    let mut animeku = AnimekuCli::new(extractor);
+    commands::sync::auto().await;
+    clearscreen_and_show_banner()?;
+
+    let input = get_user_input(title, tipe)?;
+    let animeku = AnimekuCli::new(input.tipe);
 
     clearscreen_and_show_banner()?;
-    let movie = animeku.search(&input.title).await?;
+    let movie = match input.movie {
+        Some(movie) => movie,
+        None => animeku.search(&input.title).await?,
+    };
 
     loop {
         clearscreen_and_show_banner()?;
         let episode = animeku.extract_episode(movie.clone()).await?;
         clearscreen_and_show_banner()?;
         let is_series = episode.is_series;
-        let download = animeku.extract_stream_urls(episode).await?;
+        let download = animeku.extract_stream_urls(episode.clone()).await?;
+        let url = if config.player.restream {
+            server::proxy::local_url(&download).await?
+        } else {
+            download.url.clone()
+        };
 
-        print!("{} Membuka tautan diaplikasi eksternal .. {}", "◆".blue(), "\n");
+        println!("{} {}", "◆".blue(), t!("player.opening"));
         stdout().flush()?;
 
         if cfg!(target_os = "linux") {
-            if dialoguer::Confirm::with_theme(&ColorfulTheme::default())
-            .with_prompt("Apakah kamu ingin membukanya di MPV (Jika Tersedia)")
-            .interact()?
-            {
-                let status = Command::new("mpv")
-                .arg(download.url)
-                .status()
-                .expect("Failed to start 'mpv'");
+            let player = &config.player;
+            // A failed cast shows its error and asks again.
+            let choice = loop {
+                let choice = dialoguer::Select::with_theme(&ColorfulTheme::default())
+                    .with_prompt(t!("player.choose"))
+                    .default(0)
+                    .item(t!("player.open_in", player.command))
+                    .item(t!("cast.option"))
+                    .item(t!("player.open_default"))
+                    .interact()?;
+                if choice != 1 {
+                    break choice;
+                }
+                match cast::run(&download).await {
+                    Ok(()) => break choice,
+                    Err(e) => eprintln!("{} {}", "■".red(), e),
+                }
+            };
+
+            if choice == 0 {
+                let (resume_args, watch_later) = progress::player_args(animeku.source(), &episode);
+                let status = Command::new(&player.command)
+                    .args(&player.args)
+                    .args(resume_args)
+                    .arg(url)
+                    .status()?;
 
                 if !status.success() {
-                    eprintln!(
-                        "{} Gagal menjalankan MPV",
-                        "■".red()
-                    );
+                    eprintln!("{} {}", "■".red(), t!("player.failed", player.command));
+                    break;
+                }
+                if progress::store_position(animeku.source(), &episode, watch_later)? {
+                    progress::finished(animeku.source(), &movie, &episode).await;
+                }
+            } else if choice == 1 {
+                if !is_series {
                     break;
                 }
             } else {
-                if open::that(download.url).is_ok() {
+                if open::that(url).is_ok() {
                     if !is_series {
                         break;
                     }
                     if dialoguer::Confirm::with_theme(&ColorfulTheme::default())
-                        .with_prompt("Apakah kamu ingin keluar")
+                        .with_prompt(t!("player.quit"))
                         .interact()?
                     {
                         break;
                     }
                 } else {
-                    println!("gagal");
+                    println!("{}", t!("status.failed"));
                     break;
                 };
             }
         }
     }
     println!();
+    commands::sync::auto().await;
     Ok(())
 }
 
+/// Messages follow the config of the active profile until the full config
+/// is loaded.
+fn set_lang() {
+    let language = config::read().ok().and_then(|c| c.language);
+    i18n::set_lang(language.as_deref());
+}
+
 fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    if let Some(ref path) = args.config {
+        config::set_config_path(path.clone());
+    }
+    set_lang();
+
+    if let Some(SubCommand::Profile { action }) = args.command {
+        if let Err(e) = commands::profile::run(action) {
+            println!(" {} {}\n", "■".red(), format!("{:#}", e).yellow());
+        }
+        return Ok(());
+    }
+    if let Err(e) = commands::profile::select(args.profile.clone(), args.command.is_none()) {
+        println!(" {} {}\n", "■".red(), format!("{:#}", e).yellow());
+        return Ok(());
+    }
+    set_lang();
+
+    if let Some(SubCommand::Config { action }) = args.command {
+        if let Err(e) = commands::config::run(action) {
+            println!(" {} {}\n", "■".red(), format!("{:#}", e).yellow());
+        }
+        return Ok(());
+    }
+
+    let mut config = match config::read() {
+        Ok(config) => config,
+        Err(e) => {
+            println!(" {} {}\n", "■".red(), format!("{:#}", e).yellow());
+            config::Config::default()
+        }
+    };
+    args.apply(&mut config);
+    i18n::set_lang(config.language.as_deref());
+    config::init(config);
+
     let rt = runtime::Builder::new_multi_thread().enable_all().build()?;
     rt.block_on(async {
-        if let Err(e) = app().await {
+        let result = match args.command {
+            Some(SubCommand::Serve { .. }) => server::run().await,
+            Some(SubCommand::Check {
+                daemon, interval, ..
+            }) => commands::check::run(daemon, interval).await,
+            Some(SubCommand::Calendar { output }) => commands::calendar::run(output),
+            Some(SubCommand::History { action }) => commands::history::run(action).await,
+            Some(SubCommand::Sync) => commands::sync::run().await,
+            Some(SubCommand::Feed { output, base_url }) => commands::feed::run(output, base_url),
+            Some(SubCommand::Export {
+                title,
+                format,
+                output,
+                jobs,
+            }) => match configured_source() {
+                Ok(tipe) => {
+                    commands::export::run(title.or(args.title), tipe, format, output, jobs).await
+                }
+                Err(e) => Err(e),
+            },
+            _ => app(args.title).await,
+        };
+        if let Err(e) = result {
             println!(" {} {}\n", "■".red(), format!("{:#?}", e).yellow());
         }
     });
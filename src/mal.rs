@@ -0,0 +1,287 @@
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use animeku_cli::{models::Movie, pipeline::Pipeline, regex, util::clean_title};
+use html_escape::decode_html_entities;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config,
+    watchlist::{Entry, Watchlist},
+};
+
+/// An anime from a MyAnimeList list export.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Anime {
+    pub mal_id: u64,
+    pub title: String,
+    pub episodes: usize,
+    pub watched: usize,
+    pub status: String,
+    pub tags: Vec<String>,
+}
+
+/// Maps a watchlist entry to a MAL list status.
+pub fn status(entry: &Entry) -> &'static str {
+    let has = |tag: &str| entry.tags.iter().any(|t| t.eq_ignore_ascii_case(tag));
+    if has("completed") {
+        "Completed"
+    } else if has("dropped") {
+        "Dropped"
+    } else if has("on hold") || has("on-hold") {
+        "On-Hold"
+    } else if has("watching") || entry.progress > 0 {
+        "Watching"
+    } else {
+        "Plan to Watch"
+    }
+}
+
+/// Maps a MAL list status to a watchlist tag.
+pub fn tag(status: &str) -> Option<&'static str> {
+    match status.to_lowercase().as_str() {
+        "watching" => Some("watching"),
+        "completed" => Some("completed"),
+        "dropped" => Some("dropped"),
+        "on-hold" => Some("on hold"),
+        "plan to watch" => Some("plan to watch"),
+        _ => None,
+    }
+}
+
+fn cdata(text: &str) -> String {
+    format!("<![CDATA[{}]]>", text.replace("]]>", "]]]]><![CDATA[>"))
+}
+
+pub fn to_xml(entries: &[Entry]) -> String {
+    let mut out = String::from(concat!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" ?>\n",
+        "<myanimelist>\n",
+        "  <myinfo>\n",
+        "    <user_export_type>1</user_export_type>\n",
+        "  </myinfo>\n"
+    ));
+    for entry in entries {
+        let tags = entry
+            .tags
+            .iter()
+            .filter(|t| tag(t).is_none())
+            .cloned()
+            .collect::<Vec<_>>();
+        out.push_str("  <anime>\n");
+        out.push_str(&format!(
+            "    <series_animedb_id>{}</series_animedb_id>\n",
+            entry.mal_id.unwrap_or_default()
+        ));
+        out.push_str(&format!(
+            "    <series_title>{}</series_title>\n",
+            cdata(&entry.title)
+        ));
+        out.push_str(&format!(
+            "    <series_episodes>{}</series_episodes>\n",
+            entry.episodes
+        ));
+        out.push_str(&format!(
+            "    <my_watched_episodes>{}</my_watched_episodes>\n",
+            entry.progress
+        ));
+        out.push_str(&format!("    <my_status>{}</my_status>\n", status(entry)));
+        out.push_str(&format!(
+            "    <my_tags>{}</my_tags>\n",
+            cdata(&tags.join(", "))
+        ));
+        out.push_str("    <update_on_import>1</update_on_import>\n");
+        out.push_str("  </anime>\n");
+    }
+    out.push_str("</myanimelist>\n");
+    out
+}
+
+/// Text of the flat child elements of an `<anime>` block, by tag name.
+fn fields(block: &str) -> HashMap<&str, String> {
+    regex!(r"(?s)<(\w+)>(.*?)</(\w+)>")
+        .captures_iter(block)
+        .filter(|cap| cap[1] == cap[3])
+        .map(|cap| {
+            let value = cap.get(2).map_or("", |m| m.as_str()).trim();
+            let text = match value
+                .strip_prefix("<![CDATA[")
+                .and_then(|v| v.strip_suffix("]]>"))
+            {
+                Some(raw) => raw.replace("]]]]><![CDATA[>", "]]>"),
+                None => decode_html_entities(value).to_string(),
+            };
+            (cap.get(1).map_or("", |m| m.as_str()), text)
+        })
+        .collect()
+}
+
+pub fn parse(xml: &str) -> Vec<Anime> {
+    regex!(r"(?s)<anime>(.*?)</anime>")
+        .captures_iter(xml)
+        .map(|cap| {
+            let fields = fields(&cap[1]);
+            let field = |name: &str| fields.get(name).cloned().unwrap_or_default();
+            Anime {
+                mal_id: field("series_animedb_id").parse().unwrap_or_default(),
+                title: field("series_title"),
+                episodes: field("series_episodes").parse().unwrap_or_default(),
+                watched: field("my_watched_episodes").parse().unwrap_or_default(),
+                status: field("my_status"),
+                tags: field("my_tags")
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|t| !t.is_empty())
+                    .map(String::from)
+                    .collect(),
+            }
+        })
+        .filter(|anime| !anime.title.is_empty())
+        .collect()
+}
+
+fn normalize(title: &str) -> String {
+    clean_title(title)
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .collect()
+}
+
+pub enum Match {
+    Found(Movie),
+    Review(Vec<Movie>),
+}
+
+/// Searches `pipeline` for `anime`, only a result with the same normalized
+/// title counts as found.
+pub async fn find(pipeline: &Pipeline, anime: &Anime) -> anyhow::Result<Match> {
+    let (movies, _) = pipeline.search(&clean_title(&anime.title), 1).await?;
+    let wanted = normalize(&anime.title);
+    match movies.iter().find(|m| normalize(&m.title) == wanted) {
+        Some(movie) => Ok(Match::Found(movie.clone())),
+        None => Ok(Match::Review(movies)),
+    }
+}
+
+/// Adds `movie` to the watchlist with the progress and status from `anime`.
+pub fn apply(watchlist: &mut Watchlist, source: &str, movie: &Movie, anime: &Anime) {
+    watchlist.add(Entry::new(source, movie, anime.episodes));
+    if let Some(entry) = watchlist.get_mut(source, &movie.id) {
+        entry.progress = entry.progress.max(anime.watched);
+        if anime.mal_id != 0 {
+            entry.mal_id = Some(anime.mal_id);
+        }
+        let tags = tag(&anime.status)
+            .map(String::from)
+            .into_iter()
+            .chain(anime.tags.iter().cloned());
+        for tag in tags {
+            if !entry.tags.contains(&tag) {
+                entry.tags.push(tag);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Pending {
+    pub anime: Anime,
+    pub candidates: Vec<(String, String)>,
+}
+
+/// Imported titles that could not be matched automatically.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Review {
+    pub items: Vec<Pending>,
+}
+
+pub fn review_path() -> PathBuf {
+    config::data_dir().join("review.json")
+}
+
+impl Review {
+    pub fn load() -> Self {
+        fs::read(review_path())
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        let path = review_path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn push(&mut self, anime: Anime, candidates: &[Movie]) {
+        self.items.retain(|p| p.anime.title != anime.title);
+        self.items.push(Pending {
+            anime,
+            candidates: candidates
+                .iter()
+                .map(|m| (m.id.clone(), m.title.trim().to_string()))
+                .collect(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(mal_id: Option<u64>, title: &str, progress: usize, tags: &[&str]) -> Entry {
+        Entry {
+            source: "anime".into(),
+            id: title.to_lowercase(),
+            title: title.into(),
+            episodes: 24,
+            progress,
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            mal_id,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn export_round_trips() {
+        let entries = [
+            entry(
+                Some(5114),
+                "Fullmetal Alchemist: Brotherhood",
+                64,
+                &["completed", "fav"],
+            ),
+            entry(Some(1), "Tom & Jerry ]]> <Special>", 3, &[]),
+            entry(None, "Later", 0, &["on hold"]),
+            entry(Some(2), "Planned", 0, &[]),
+        ];
+        let parsed = parse(&to_xml(&entries));
+
+        assert_eq!(parsed.len(), entries.len());
+        for (entry, anime) in entries.iter().zip(&parsed) {
+            assert_eq!(anime.mal_id, entry.mal_id.unwrap_or_default());
+            assert_eq!(anime.title, entry.title);
+            assert_eq!(anime.episodes, entry.episodes);
+            assert_eq!(anime.watched, entry.progress);
+            assert_eq!(anime.status, status(entry));
+        }
+        let statuses = parsed.iter().map(|a| tag(&a.status)).collect::<Vec<_>>();
+        assert_eq!(
+            statuses,
+            [
+                Some("completed"),
+                Some("watching"),
+                Some("on hold"),
+                Some("plan to watch")
+            ]
+        );
+        assert_eq!(parsed[0].tags, ["fav"]);
+        assert!(parsed[1].tags.is_empty());
+    }
+}
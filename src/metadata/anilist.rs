@@ -0,0 +1,112 @@
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::{json, Value};
+
+use super::{names, strip_html, Details, MetaProvider};
+use crate::config::{self, http_client};
+
+static QUERY: &str = r#"query ($search: String) {
+  Media(search: $search, type: ANIME) {
+    title { english native }
+    description(asHtml: false)
+    averageScore
+    genres
+    coverImage { extraLarge }
+    studios(isMain: true) { nodes { name } }
+  }
+}"#;
+
+pub struct Anilist {
+    client: Client,
+    endpoint: String,
+}
+
+impl Anilist {
+    pub fn new() -> Self {
+        Self {
+            client: http_client(),
+            endpoint: config::get().anilist.endpoint.clone(),
+        }
+    }
+}
+
+impl Default for Anilist {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl MetaProvider for Anilist {
+    fn name(&self) -> &'static str {
+        "anilist"
+    }
+
+    async fn lookup(&self, title: &str, _year: Option<u32>) -> anyhow::Result<Option<Details>> {
+        let resp: Value = self
+            .client
+            .post(&self.endpoint)
+            .json(&json!({ "query": QUERY, "variables": { "search": title } }))
+            .send()
+            .await?
+            .json()
+            .await?;
+
+        let media = &resp["data"]["Media"];
+        if media.is_null() {
+            return Ok(None);
+        }
+        let text = |value: &Value| value.as_str().map(String::from);
+        Ok(Some(Details {
+            title_english: text(&media["title"]["english"]),
+            title_japanese: text(&media["title"]["native"]),
+            synopsis: media["description"].as_str().map(strip_html),
+            studio: text(&media["studios"]["nodes"][0]["name"]),
+            score: media["averageScore"].as_f64().map(|s| s / 10.0),
+            poster: text(&media["coverImage"]["extraLarge"]),
+            genres: names(&media["genres"], None),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{routing::post, Json, Router};
+
+    use super::*;
+
+    async fn graphql(Json(body): Json<Value>) -> Json<Value> {
+        if body["variables"]["search"] == "Nothing" {
+            return Json(json!({ "data": { "Media": null } }));
+        }
+        Json(json!({ "data": { "Media": {
+            "title": { "english": "Frieren", "native": "葬送のフリーレン" },
+            "description": "An elf<br>mage &amp; friends.",
+            "averageScore": 91,
+            "genres": ["Adventure", "Fantasy"],
+            "coverImage": { "extraLarge": "http://img/frieren.jpg" },
+            "studios": { "nodes": [{ "name": "Madhouse" }] }
+        } } }))
+    }
+
+    #[tokio::test]
+    async fn parses_media() {
+        let app = Router::new().route("/", post(graphql));
+        let server = axum::Server::bind(&([127, 0, 0, 1], 0).into()).serve(app.into_make_service());
+        let anilist = Anilist {
+            client: Client::new(),
+            endpoint: format!("http://{}/", server.local_addr()),
+        };
+        tokio::spawn(server);
+
+        let details = anilist.lookup("Frieren", None).await.unwrap().unwrap();
+        assert_eq!(details.title_english.as_deref(), Some("Frieren"));
+        assert_eq!(details.title_japanese.as_deref(), Some("葬送のフリーレン"));
+        assert_eq!(details.synopsis.as_deref(), Some("An elf\nmage & friends."));
+        assert_eq!(details.studio.as_deref(), Some("Madhouse"));
+        assert_eq!(details.score, Some(9.1));
+        assert_eq!(details.poster.as_deref(), Some("http://img/frieren.jpg"));
+        assert_eq!(details.genres, ["Adventure", "Fantasy"]);
+        assert_eq!(anilist.lookup("Nothing", None).await.unwrap(), None);
+    }
+}
@@ -0,0 +1,122 @@
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::Value;
+
+use super::{names, Details, MetaProvider};
+use crate::config::{self, http_client};
+
+pub struct Jikan {
+    client: Client,
+    endpoint: String,
+}
+
+impl Jikan {
+    pub fn new() -> Self {
+        Self {
+            client: http_client(),
+            endpoint: config::get().metadata.jikan_endpoint.clone(),
+        }
+    }
+}
+
+impl Default for Jikan {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl MetaProvider for Jikan {
+    fn name(&self) -> &'static str {
+        "jikan"
+    }
+
+    async fn lookup(&self, title: &str, year: Option<u32>) -> anyhow::Result<Option<Details>> {
+        let resp: Value = self
+            .client
+            .get(format!("{}/anime", self.endpoint.trim_end_matches('/')))
+            .query(&[("q", title), ("limit", "5")])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let results = resp["data"].as_array().cloned().unwrap_or_default();
+        let anime = results
+            .iter()
+            .find(|a| year.is_some() && a["year"].as_u64() == year.map(u64::from))
+            .or(results.first());
+        let Some(anime) = anime else {
+            return Ok(None);
+        };
+
+        let text = |value: &Value| value.as_str().map(String::from);
+        Ok(Some(Details {
+            title_english: text(&anime["title_english"]),
+            title_japanese: text(&anime["title_japanese"]),
+            synopsis: text(&anime["synopsis"]),
+            studio: text(&anime["studios"][0]["name"]),
+            score: anime["score"].as_f64(),
+            poster: text(&anime["images"]["jpg"]["large_image_url"]),
+            genres: [&anime["genres"], &anime["explicit_genres"]]
+                .into_iter()
+                .flat_map(|genres| names(genres, Some("name")))
+                .collect(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use axum::{extract::Query, routing::get, Json, Router};
+    use serde_json::json;
+
+    use super::*;
+
+    fn anime(title: &str, year: u64) -> Value {
+        json!({
+            "title_english": title,
+            "title_japanese": "ハンター",
+            "synopsis": format!("{} synopsis", title),
+            "studios": [{ "name": "Madhouse" }],
+            "score": 9.04,
+            "year": year,
+            "genres": [{ "name": "Action" }],
+            "explicit_genres": [{ "name": "Ecchi" }],
+            "images": { "jpg": { "large_image_url": format!("http://img/{}.jpg", year) } }
+        })
+    }
+
+    async fn search(Query(query): Query<HashMap<String, String>>) -> Json<Value> {
+        if query.get("q").map(String::as_str) == Some("Nothing") {
+            return Json(json!({ "data": [] }));
+        }
+        Json(json!({ "data": [anime("Hunter 1999", 1999), anime("Hunter 2011", 2011)] }))
+    }
+
+    #[tokio::test]
+    async fn parses_and_prefers_matching_year() {
+        let app = Router::new().route("/anime", get(search));
+        let server = axum::Server::bind(&([127, 0, 0, 1], 0).into()).serve(app.into_make_service());
+        let jikan = Jikan {
+            client: Client::new(),
+            endpoint: format!("http://{}/", server.local_addr()),
+        };
+        tokio::spawn(server);
+
+        let details = jikan.lookup("Hunter", Some(2011)).await.unwrap().unwrap();
+        assert_eq!(details.title_english.as_deref(), Some("Hunter 2011"));
+        assert_eq!(details.title_japanese.as_deref(), Some("ハンター"));
+        assert_eq!(details.studio.as_deref(), Some("Madhouse"));
+        assert_eq!(details.score, Some(9.04));
+        assert_eq!(details.poster.as_deref(), Some("http://img/2011.jpg"));
+        assert_eq!(details.genres, ["Action", "Ecchi"]);
+
+        let first = jikan.lookup("Hunter", None).await.unwrap().unwrap();
+        assert_eq!(first.title_english.as_deref(), Some("Hunter 1999"));
+        assert_eq!(jikan.lookup("Nothing", None).await.unwrap(), None);
+    }
+}
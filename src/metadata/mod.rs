@@ -0,0 +1,196 @@
+use std::{collections::HashMap, fs, path::PathBuf, sync::Arc};
+
+use animeku_cli::{
+    i18n::meta_key,
+    models::{Meta, Movie},
+    pipeline::Enrich,
+    regex, t,
+    util::clean_title,
+};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    config::{self, AnimeProvider},
+    watchlist::now,
+};
+
+pub mod anilist;
+pub mod jikan;
+pub mod tmdb;
+
+static CACHE_TTL: u64 = 7 * 24 * 60 * 60;
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Details {
+    pub title_english: Option<String>,
+    pub title_japanese: Option<String>,
+    pub synopsis: Option<String>,
+    pub studio: Option<String>,
+    pub score: Option<f64>,
+    pub poster: Option<String>,
+    pub genres: Vec<String>,
+}
+
+#[async_trait]
+pub trait MetaProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+    async fn lookup(&self, title: &str, year: Option<u32>) -> anyhow::Result<Option<Details>>;
+}
+
+pub fn provider(source: &str) -> Option<Arc<dyn MetaProvider>> {
+    let metadata = &config::get().metadata;
+    if !metadata.enabled {
+        return None;
+    }
+    match source {
+        "tenflix" => Some(Arc::new(tmdb::Tmdb::new(metadata.tmdb_key.clone()?))),
+        _ => match metadata.anime {
+            AnimeProvider::Anilist => Some(Arc::new(anilist::Anilist::new())),
+            AnimeProvider::Jikan => Some(Arc::new(jikan::Jikan::new())),
+        },
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+struct Cached {
+    fetched: u64,
+    details: Option<Details>,
+}
+
+fn cache_path() -> PathBuf {
+    config::cache_dir().join("metadata.json")
+}
+
+fn load_cache() -> HashMap<String, Cached> {
+    fs::read(cache_path())
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default()
+}
+
+fn save_cache(cache: &HashMap<String, Cached>) {
+    let path = cache_path();
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    if let Ok(bytes) = serde_json::to_vec(cache) {
+        let _ = fs::write(path, bytes);
+    }
+}
+
+pub fn strip_html(text: &str) -> String {
+    let text = regex!(r"(?i)<br\s*/?>").replace_all(text, "\n");
+    let text = regex!(r"<[^>]+>").replace_all(&text, "");
+    html_escape::decode_html_entities(text.trim()).to_string()
+}
+
+/// The strings of a JSON array, or its objects' `key` field.
+fn names(value: &Value, key: Option<&str>) -> Vec<String> {
+    value
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|v| match key {
+            Some(key) => v[key].as_str(),
+            None => v.as_str(),
+        })
+        .map(String::from)
+        .collect()
+}
+
+/// Looks `title` up with `provider`, answering from the cache when possible.
+pub async fn lookup(
+    provider: &dyn MetaProvider,
+    title: &str,
+    year: Option<u32>,
+) -> anyhow::Result<Option<Details>> {
+    let title = clean_title(title);
+    let key = format!("{}:{}", provider.name(), title.to_lowercase());
+    let mut cache = load_cache();
+    if let Some(cached) = cache.get(&key) {
+        if now().saturating_sub(cached.fetched) < CACHE_TTL {
+            return Ok(cached.details.clone());
+        }
+    }
+
+    let details = provider.lookup(&title, year).await?;
+    cache.insert(
+        key,
+        Cached {
+            fetched: now(),
+            details: details.clone(),
+        },
+    );
+    save_cache(&cache);
+    Ok(details)
+}
+
+/// Enriches the details fetched by a pipeline, so the content filter also
+/// sees the provider genres. Failed lookups go to `report`, the details of
+/// the source are kept as they are.
+struct Enricher<F> {
+    source: &'static str,
+    report: F,
+}
+
+#[async_trait]
+impl<F: Fn(String) + Send + Sync> Enrich for Enricher<F> {
+    async fn enrich(&self, movie: &Movie, meta: &mut Meta) {
+        if let Err(e) = enrich(self.source, &movie.title, meta).await {
+            (self.report)(t!(
+                "metadata.failed",
+                movie.title.trim(),
+                format!("{:#}", e)
+            ));
+        }
+    }
+}
+
+pub fn enricher(
+    source: &'static str,
+    report: impl Fn(String) + Send + Sync + 'static,
+) -> Arc<dyn Enrich> {
+    Arc::new(Enricher { source, report })
+}
+
+/// Adds the provider details to `meta`, keeping the fields the source
+/// already has.
+pub async fn enrich(source: &str, title: &str, meta: &mut Meta) -> anyhow::Result<()> {
+    let Some(provider) = provider(source) else {
+        return Ok(());
+    };
+    let year = meta
+        .data
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case("tahun") || k.eq_ignore_ascii_case("year"))
+        .and_then(|(_, v)| regex!(r"\d{4}").find(v))
+        .and_then(|m| m.as_str().parse().ok());
+    let Some(details) = lookup(provider.as_ref(), title, year).await? else {
+        return Ok(());
+    };
+
+    // Labels are stored translated, `meta_key` matches a source label in
+    // either language.
+    let mut add = |key: &str, value: Option<String>| {
+        let key = meta_key(key);
+        let exists = meta.data.iter().any(|(k, _)| meta_key(k) == key);
+        if let Some(value) = value.filter(|v| !v.trim().is_empty() && !exists) {
+            meta.data.push((key, value.trim().to_string()));
+        }
+    };
+    add("Judul Inggris", details.title_english);
+    add("Judul Jepang", details.title_japanese);
+    add("Studio", details.studio);
+    add("Skor", details.score.map(|s| format!("{:.1}/10", s)));
+    add("Sinopsis", details.synopsis);
+    add("Genre", Some(details.genres.join(", ")));
+
+    if details.poster.is_some() {
+        meta.thumb_url = details.poster;
+    }
+    Ok(())
+}
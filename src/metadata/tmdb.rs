@@ -0,0 +1,167 @@
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::Value;
+
+use super::{names, Details, MetaProvider};
+use crate::config::{self, http_client};
+
+static IMAGE_BASE: &str = "https://image.tmdb.org/t/p/original";
+
+pub struct Tmdb {
+    client: Client,
+    endpoint: String,
+    key: String,
+}
+
+impl Tmdb {
+    pub fn new(key: String) -> Self {
+        Self {
+            client: http_client(),
+            endpoint: config::get()
+                .metadata
+                .tmdb_endpoint
+                .trim_end_matches('/')
+                .to_string(),
+            key,
+        }
+    }
+
+    async fn get(&self, path: &str, query: &[(&str, &str)]) -> anyhow::Result<Value> {
+        Ok(self
+            .client
+            .get(format!("{}{}", self.endpoint, path))
+            .query(&[("api_key", self.key.as_str()), ("language", "en-US")])
+            .query(query)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?)
+    }
+}
+
+#[async_trait]
+impl MetaProvider for Tmdb {
+    fn name(&self) -> &'static str {
+        "tmdb"
+    }
+
+    async fn lookup(&self, title: &str, year: Option<u32>) -> anyhow::Result<Option<Details>> {
+        let resp = self.get("/search/multi", &[("query", title)]).await?;
+        let results = resp["results"]
+            .as_array()
+            .cloned()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|r| matches!(r["media_type"].as_str(), Some("movie" | "tv")))
+            .collect::<Vec<_>>();
+        let released = |r: &Value| {
+            r["release_date"]
+                .as_str()
+                .or(r["first_air_date"].as_str())
+                .and_then(|d| d.get(..4))
+                .and_then(|y| y.parse::<u32>().ok())
+        };
+        let found = results
+            .iter()
+            .find(|r| year.is_some() && released(r) == year)
+            .or(results.first());
+        let Some(found) = found else {
+            return Ok(None);
+        };
+
+        let kind = found["media_type"].as_str().unwrap_or("movie");
+        let id = found["id"].as_u64().unwrap_or_default();
+        let details = self.get(&format!("/{}/{}", kind, id), &[]).await?;
+
+        let text = |value: &Value| value.as_str().filter(|s| !s.is_empty()).map(String::from);
+        let original = (details["original_language"] == "ja")
+            .then(|| text(&details["original_title"]).or(text(&details["original_name"])))
+            .flatten();
+        Ok(Some(Details {
+            title_english: text(&details["title"]).or(text(&details["name"])),
+            title_japanese: original,
+            synopsis: text(&details["overview"]),
+            studio: text(&details["production_companies"][0]["name"]),
+            score: details["vote_average"].as_f64().filter(|s| *s > 0.0),
+            poster: text(&details["poster_path"]).map(|p| format!("{}{}", IMAGE_BASE, p)),
+            genres: names(&details["genres"], Some("name")),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use axum::{
+        extract::{Path, Query},
+        http::StatusCode,
+        routing::get,
+        Json, Router,
+    };
+    use serde_json::json;
+
+    use super::*;
+
+    async fn search(Query(query): Query<HashMap<String, String>>) -> (StatusCode, Json<Value>) {
+        if query.get("api_key").map(String::as_str) != Some("key") {
+            return (StatusCode::UNAUTHORIZED, Json(json!({})));
+        }
+        let results = json!([
+            { "media_type": "person", "id": 1 },
+            { "media_type": "movie", "id": 10, "release_date": "2001-07-20" },
+            { "media_type": "tv", "id": 20, "first_air_date": "2019-04-06" }
+        ]);
+        (StatusCode::OK, Json(json!({ "results": results })))
+    }
+
+    async fn details(Path((kind, id)): Path<(String, u64)>) -> Json<Value> {
+        Json(json!({
+            "name": format!("{} {}", kind, id),
+            "original_name": "鬼滅の刃",
+            "original_language": "ja",
+            "overview": "",
+            "production_companies": [{ "name": "ufotable" }],
+            "vote_average": 8.7,
+            "poster_path": "/poster.jpg",
+            "genres": [{ "id": 16, "name": "Animation" }, { "id": 10759, "name": "Action & Adventure" }]
+        }))
+    }
+
+    #[tokio::test]
+    async fn parses_details_of_matching_year() {
+        let app = Router::new()
+            .route("/search/multi", get(search))
+            .route("/:kind/:id", get(details));
+        let server = axum::Server::bind(&([127, 0, 0, 1], 0).into()).serve(app.into_make_service());
+        let endpoint = format!("http://{}", server.local_addr());
+        tokio::spawn(server);
+        let tmdb = |key: &str| Tmdb {
+            client: Client::new(),
+            endpoint: endpoint.clone(),
+            key: key.into(),
+        };
+
+        let details = tmdb("key")
+            .lookup("Demon Slayer", Some(2019))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(details.title_english.as_deref(), Some("tv 20"));
+        assert_eq!(details.title_japanese.as_deref(), Some("鬼滅の刃"));
+        assert_eq!(details.synopsis, None);
+        assert_eq!(details.studio.as_deref(), Some("ufotable"));
+        assert_eq!(details.score, Some(8.7));
+        assert_eq!(details.poster, Some(format!("{}/poster.jpg", IMAGE_BASE)));
+        assert_eq!(details.genres, ["Animation", "Action & Adventure"]);
+
+        let first = tmdb("key")
+            .lookup("Spirited Away", None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(first.title_english.as_deref(), Some("movie 10"));
+        assert!(tmdb("wrong").lookup("Spirited Away", None).await.is_err());
+    }
+}
@@ -1,13 +1,11 @@
-pub struct Input {
-    pub title: String,
-    pub tipe: usize,
-}
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct Movie {
     pub id: String,
     pub title: String,
     pub total_episodes: Option<String>,
+    pub meta: Meta,
 }
 
 impl std::fmt::Display for Movie {
@@ -20,7 +18,7 @@ impl std::fmt::Display for Movie {
     }
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct Episode {
     pub id: String,
     pub title: String,
@@ -33,10 +31,12 @@ impl std::fmt::Display for Episode {
     }
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct Stream {
     pub url: String,
     pub title: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub headers: Vec<(String, String)>,
 }
 
 impl std::fmt::Display for Stream {
@@ -45,7 +45,7 @@ impl std::fmt::Display for Stream {
     }
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct Meta {
     pub thumb_url: Option<String>,
     pub data: Vec<(String, String)>,
@@ -0,0 +1,300 @@
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use anyhow::bail;
+use async_trait::async_trait;
+use futures::{stream, StreamExt};
+
+use crate::{
+    ext::{
+        self,
+        config::{ExtConfig, FilterConfig},
+        filter, Ext,
+    },
+    models::{Episode, Meta, Movie, Stream},
+    t,
+};
+
+static MAX_ENTRIES: usize = 256;
+static SEARCH_TTL: Duration = Duration::from_secs(30 * 60);
+static EPISODE_TTL: Duration = Duration::from_secs(10 * 60);
+/// Stream URLs are often signed and expire, so they are only reused briefly.
+static STREAM_TTL: Duration = Duration::from_secs(3 * 60);
+
+/// Keeps at most `MAX_ENTRIES` results for `ttl`, dropping the oldest first.
+struct Cache<K, V> {
+    entries: Mutex<HashMap<K, (Instant, V)>>,
+    ttl: Duration,
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> Cache<K, V> {
+    fn new(ttl: Duration) -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            ttl,
+        }
+    }
+
+    fn get(&self, key: &K) -> Option<V> {
+        self.entries
+            .lock()
+            .unwrap()
+            .get(key)
+            .filter(|(added, _)| added.elapsed() < self.ttl)
+            .map(|(_, value)| value.clone())
+    }
+
+    fn insert(&self, key: K, value: V) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, (added, _)| added.elapsed() < self.ttl);
+        if entries.len() >= MAX_ENTRIES && !entries.contains_key(&key) {
+            let oldest = entries
+                .iter()
+                .min_by_key(|(_, (added, _))| *added)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                entries.remove(&oldest);
+            }
+        }
+        entries.insert(key, (Instant::now(), value));
+    }
+}
+
+/// Adds details from outside the source, such as a metadata provider, to
+/// the details of a title.
+#[async_trait]
+pub trait Enrich: Send + Sync {
+    async fn enrich(&self, movie: &Movie, meta: &mut Meta);
+}
+
+pub struct Pipeline {
+    extractor: Arc<dyn Ext>,
+    filter: Option<FilterConfig>,
+    enricher: Option<Arc<dyn Enrich>>,
+    movie_cache: Cache<(String, usize), (Vec<Movie>, u64)>,
+    episode_cache: Cache<String, (Vec<Episode>, Meta)>,
+    stream_cache: Cache<String, Vec<Stream>>,
+}
+
+impl Pipeline {
+    pub fn new(extractor: Arc<dyn Ext>) -> Self {
+        Self {
+            extractor,
+            filter: None,
+            enricher: None,
+            movie_cache: Cache::new(SEARCH_TTL),
+            episode_cache: Cache::new(EPISODE_TTL),
+            stream_cache: Cache::new(STREAM_TTL),
+        }
+    }
+
+    /// The pipeline of a source, with the content filter of `config`.
+    pub fn for_source(id: usize, config: &ExtConfig) -> Self {
+        Self::new(ext::get_ext(id, config)).with_filter(&config.filter)
+    }
+
+    pub fn from_source(name: &str, config: &ExtConfig) -> anyhow::Result<Self> {
+        Ok(Self::for_source(ext::source_index(name)?, config))
+    }
+
+    /// Hides the titles `filter` blocks, if it is enabled.
+    pub fn with_filter(mut self, filter: &FilterConfig) -> Self {
+        self.filter = filter.enabled.then(|| filter.clone());
+        self
+    }
+
+    /// Runs `enricher` on every fetched detail, before the filter sees it.
+    pub fn with_enricher(mut self, enricher: Arc<dyn Enrich>) -> Self {
+        self.enricher = Some(enricher);
+        self
+    }
+
+    pub fn extractor(&self) -> Arc<dyn Ext> {
+        self.extractor.clone()
+    }
+
+    pub async fn search(&self, title: &str, page: usize) -> anyhow::Result<(Vec<Movie>, u64)> {
+        let key = (title.to_string(), page);
+        if let Some(result) = self.movie_cache.get(&key) {
+            return Ok(result);
+        }
+        let (movies, total) = self.extractor.search(title.into(), page).await?;
+        let result = match self.filter {
+            Some(ref filter) => {
+                let count = movies.len();
+                let movies = stream::iter(movies)
+                    .map(|movie| async move { self.allowed(filter, &movie).await.then_some(movie) })
+                    .buffered(4)
+                    .filter_map(|movie| async move { movie })
+                    .collect::<Vec<_>>()
+                    .await;
+                let hidden = (count - movies.len()) as u64;
+                (movies, total.saturating_sub(hidden))
+            }
+            None => (movies, total),
+        };
+        self.movie_cache.insert(key, result.clone());
+        Ok(result)
+    }
+
+    /// Checks a title, fetching its details when the search result carries
+    /// no genre or rating. The details stay cached for when the title is
+    /// opened, titles whose details cannot be fetched are hidden.
+    async fn allowed(&self, filter: &FilterConfig, movie: &Movie) -> bool {
+        if filter::blocked(filter, &movie.meta) {
+            return false;
+        }
+        if filter::rated(&movie.meta) {
+            return true;
+        }
+        match self.details(movie.clone()).await {
+            Ok((_, meta)) => !filter::blocked(filter, &meta),
+            Err(_) => false,
+        }
+    }
+
+    async fn details(&self, movie: Movie) -> anyhow::Result<(Vec<Episode>, Meta)> {
+        if let Some(result) = self.episode_cache.get(&movie.id) {
+            return Ok(result);
+        }
+        let id = movie.id.clone();
+        let (episodes, mut meta) = self.extractor.get_episodes(movie.clone()).await?;
+        if let Some(ref enricher) = self.enricher {
+            enricher.enrich(&movie, &mut meta).await;
+        }
+        let result = (episodes, meta);
+        self.episode_cache.insert(id, result.clone());
+        Ok(result)
+    }
+
+    pub async fn episodes(&self, movie: Movie) -> anyhow::Result<(Vec<Episode>, Meta)> {
+        let title = movie.title.clone();
+        let result = self.details(movie).await?;
+        if let Some(ref filter) = self.filter {
+            if filter::blocked(filter, &result.1) {
+                bail!(t!("filter.blocked", title.trim()));
+            }
+        }
+        Ok(result)
+    }
+
+    pub async fn streams(&self, episode: Episode) -> anyhow::Result<Vec<Stream>> {
+        if let Some(streams) = self.stream_cache.get(&episode.id) {
+            return Ok(streams);
+        }
+        let id = episode.id.clone();
+        let streams = self.extractor.get_stream_urls(episode).await?;
+        self.stream_cache.insert(id, streams.clone());
+        Ok(streams)
+    }
+
+    pub async fn resolve(
+        &self,
+        episode: Episode,
+        quality: Option<&str>,
+    ) -> anyhow::Result<Option<Stream>> {
+        let streams = self.streams(episode).await?;
+        Ok(pick_stream(&streams, quality)
+            .or_else(|| streams.first())
+            .cloned())
+    }
+}
+
+#[async_trait]
+impl Ext for Pipeline {
+    async fn search(&self, title: String, page: usize) -> anyhow::Result<(Vec<Movie>, u64)> {
+        Pipeline::search(self, &title, page).await
+    }
+
+    async fn get_episodes(&self, movie: Movie) -> anyhow::Result<(Vec<Episode>, Meta)> {
+        self.episodes(movie).await
+    }
+
+    async fn get_stream_urls(&self, episode: Episode) -> anyhow::Result<Vec<Stream>> {
+        self.streams(episode).await
+    }
+}
+
+pub fn pick_stream<'a>(streams: &'a [Stream], quality: Option<&str>) -> Option<&'a Stream> {
+    let quality = quality?.to_lowercase();
+    streams
+        .iter()
+        .find(|s| s.title.to_lowercase().contains(&quality))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    /// Two untagged titles, the second one only tagged by the enricher.
+    #[derive(Default)]
+    struct Stub {
+        details: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Ext for Stub {
+        async fn search(&self, _: String, _: usize) -> anyhow::Result<(Vec<Movie>, u64)> {
+            let movie = |id: &str| Movie {
+                id: id.into(),
+                title: id.into(),
+                ..Default::default()
+            };
+            Ok((vec![movie("safe"), movie("ecchi")], 2))
+        }
+
+        async fn get_episodes(&self, _: Movie) -> anyhow::Result<(Vec<Episode>, Meta)> {
+            self.details.fetch_add(1, Ordering::SeqCst);
+            Ok((vec![Episode::default()], Meta::default()))
+        }
+
+        async fn get_stream_urls(&self, _: Episode) -> anyhow::Result<Vec<Stream>> {
+            Ok(vec![])
+        }
+    }
+
+    struct Genres;
+
+    #[async_trait]
+    impl Enrich for Genres {
+        async fn enrich(&self, movie: &Movie, meta: &mut Meta) {
+            if movie.id == "ecchi" {
+                meta.data.push(("Genre".into(), "Comedy, Ecchi".into()));
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn filters_once_with_enriched_details() {
+        let stub = Arc::new(Stub::default());
+        let filter = FilterConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        let pipeline = Pipeline::new(stub.clone())
+            .with_filter(&filter)
+            .with_enricher(Arc::new(Genres));
+
+        let (movies, total) = pipeline.search("x", 1).await.unwrap();
+        assert_eq!(
+            movies.iter().map(|m| m.id.as_str()).collect::<Vec<_>>(),
+            ["safe"]
+        );
+        assert_eq!(total, 1);
+        assert_eq!(stub.details.load(Ordering::SeqCst), 2);
+
+        pipeline.episodes(movies[0].clone()).await.unwrap();
+        let blocked = Movie {
+            id: "ecchi".into(),
+            ..Default::default()
+        };
+        assert!(pipeline.episodes(blocked).await.is_err());
+        assert_eq!(stub.details.load(Ordering::SeqCst), 2);
+    }
+}
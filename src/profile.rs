@@ -0,0 +1,149 @@
+use std::{fs, path::PathBuf};
+
+use animeku_cli::{regex, t};
+use anyhow::bail;
+use pbkdf2::pbkdf2_hmac;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use crate::config;
+
+pub static DEFAULT_PROFILE: &str = "default";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Profile {
+    pub name: String,
+    /// Salted PBKDF2 hash of the PIN, see `hash_pin`.
+    pub pin: Option<String>,
+}
+
+impl Profile {
+    pub fn check_pin(&self, pin: &str) -> bool {
+        match self.pin {
+            Some(ref hash) => *hash == hash_pin(&profile_domain(&self.name), pin),
+            None => true,
+        }
+    }
+
+    pub fn data_dir(&self) -> PathBuf {
+        config::base_data_dir().join("profiles").join(&self.name)
+    }
+}
+
+impl std::fmt::Display for Profile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)?;
+        if self.pin.is_some() {
+            write!(f, " 🔒")?;
+        }
+        Ok(())
+    }
+}
+
+static PIN_ROUNDS: u32 = 600_000;
+
+/// Random salt of this install, created on first use.
+fn install_salt() -> anyhow::Result<String> {
+    let path = config::base_data_dir().join("pin_salt");
+    if let Ok(salt) = fs::read_to_string(&path) {
+        if !salt.trim().is_empty() {
+            return Ok(salt.trim().to_string());
+        }
+    }
+    let salt = hex(&rand::random::<[u8; 16]>());
+    fs::create_dir_all(config::base_data_dir())?;
+    fs::write(path, &salt)?;
+    Ok(salt)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Domain of a profile PIN. Names cannot contain `:`, so it never matches
+/// another domain such as the content filter's.
+pub fn profile_domain(name: &str) -> String {
+    format!("profile:{}", name)
+}
+
+/// PBKDF2-HMAC-SHA256 of `pin`, salted with the install salt and `domain`.
+/// Without a readable salt no PIN matches.
+pub fn hash_pin(domain: &str, pin: &str) -> String {
+    let Ok(salt) = install_salt() else {
+        return String::new();
+    };
+    let mut key = [0u8; 32];
+    pbkdf2_hmac::<Sha256>(
+        pin.as_bytes(),
+        format!("{}:{}", salt, domain).as_bytes(),
+        PIN_ROUNDS,
+        &mut key,
+    );
+    hex(&key)
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Profiles {
+    pub profiles: Vec<Profile>,
+    /// PIN hash of the default profile.
+    pub default_pin: Option<String>,
+}
+
+pub fn path() -> PathBuf {
+    config::base_data_dir().join("profiles.json")
+}
+
+impl Profiles {
+    pub fn load() -> Self {
+        fs::read(path())
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        let path = path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// The profile used when no other profile is active.
+    pub fn default_profile(&self) -> Profile {
+        Profile {
+            name: DEFAULT_PROFILE.into(),
+            pin: self.default_pin.clone(),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Profile> {
+        self.profiles.iter().find(|p| p.name == name)
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Profile> {
+        self.profiles.iter_mut().find(|p| p.name == name)
+    }
+
+    pub fn add(&mut self, name: &str, pin: Option<&str>) -> anyhow::Result<()> {
+        if !regex!(r"^[\w-]{1,32}$").is_match(name) {
+            bail!(t!("profile.invalid_name", name));
+        }
+        if self.get(name).is_some() {
+            bail!(t!("profile.exists", name));
+        }
+        self.profiles.push(Profile {
+            name: name.into(),
+            pin: pin.map(|pin| hash_pin(&profile_domain(name), pin)),
+        });
+        Ok(())
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Profile> {
+        let index = self.profiles.iter().position(|p| p.name == name)?;
+        Some(self.profiles.remove(index))
+    }
+}
@@ -0,0 +1,168 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use animeku_cli::{
+    models::{Episode, Movie},
+    regex, t,
+    util::{clean_title, episode_number},
+};
+use colored::Colorize;
+use dialoguer::theme::ColorfulTheme;
+
+use crate::{
+    anilist::{Anilist, Mapping, Media},
+    config, history, input,
+    resume::Positions,
+};
+
+fn is_mpv() -> bool {
+    Path::new(&config::get().player.command)
+        .file_stem()
+        .is_some_and(|stem| stem.to_string_lossy().starts_with("mpv"))
+}
+
+/// Watch-later folder of one mpv run, so players running side by side don't
+/// read or clear each other's position.
+pub struct WatchLater {
+    dir: PathBuf,
+}
+
+impl WatchLater {
+    fn new() -> Option<Self> {
+        let dir = config::cache_dir().join("watch_later").join(format!(
+            "{}-{:08x}",
+            std::process::id(),
+            rand::random::<u32>()
+        ));
+        fs::create_dir_all(&dir).ok()?;
+        Some(Self { dir })
+    }
+}
+
+impl Drop for WatchLater {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// Extra mpv arguments that resume `episode` and save where playback stops.
+pub fn player_args(source: &str, episode: &Episode) -> (Vec<String>, Option<WatchLater>) {
+    let Some(watch_later) = is_mpv().then(WatchLater::new).flatten() else {
+        return (Vec::new(), None);
+    };
+
+    let mut args = vec![
+        "--save-position-on-quit".to_string(),
+        format!("--watch-later-directory={}", watch_later.dir.display()),
+    ];
+    if let Some(seconds) = Positions::load().get(source, &episode.id) {
+        args.push(format!("--start={}", seconds));
+    }
+    (args, Some(watch_later))
+}
+
+/// Stores where mpv stopped, returns whether the episode was watched to the
+/// end. mpv leaves no watch-later file at the end of a file; other players
+/// can't tell, so the user is asked.
+pub fn store_position(
+    source: &str,
+    episode: &Episode,
+    watch_later: Option<WatchLater>,
+) -> anyhow::Result<bool> {
+    let Some(watch_later) = watch_later else {
+        return Ok(dialoguer::Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt(t!("player.watched"))
+            .default(true)
+            .interact()?);
+    };
+    let seconds = fs::read_dir(&watch_later.dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|file| fs::read_to_string(file.path()).ok())
+        .find_map(|body| {
+            regex!(r"(?m)^start=([\d.]+)")
+                .captures(&body)
+                .and_then(|cap| cap[1].parse::<f64>().ok())
+        });
+
+    let mut positions = Positions::load();
+    positions.set(source, &episode.id, seconds.unwrap_or_default());
+    if let Err(e) = positions.save() {
+        eprintln!("{} {}", "■".red(), format!("{:#}", e).yellow());
+    }
+    Ok(seconds.is_none())
+}
+
+async fn media(anilist: &Anilist, source: &str, movie: &Movie) -> anyhow::Result<Option<Media>> {
+    let mut mapping = Mapping::load();
+    if let Some(media) = mapping.get(source, &movie.id) {
+        return Ok(media.clone());
+    }
+
+    let results = anilist.search(&clean_title(&movie.title)).await?;
+    if results.is_empty() {
+        println!(
+            "{} {}",
+            "■".red(),
+            t!("anilist.not_found", movie.title.trim()).yellow()
+        );
+        return Ok(None);
+    }
+
+    println!(
+        "{} {}",
+        "◆".blue(),
+        t!("anilist.confirm", movie.title.trim().green())
+    );
+    let mut options = results;
+    options.push(Media {
+        title: t!("anilist.ignore").into(),
+        ..Default::default()
+    });
+    let selected = input::choice(options, true)?;
+    let media = (selected.id != 0).then_some(selected);
+    mapping.set(source, &movie.id, media.clone());
+    mapping.save()?;
+    Ok(media)
+}
+
+async fn push(
+    anilist: &Anilist,
+    source: &str,
+    movie: &Movie,
+    episode: &Episode,
+) -> anyhow::Result<()> {
+    let progress = match episode_number(&episode.title) {
+        Some(number) => number,
+        None if !episode.is_series => 1,
+        None => return Ok(()),
+    };
+    let Some(media) = media(anilist, source, movie).await? else {
+        return Ok(());
+    };
+    if anilist.save_progress(&media, progress).await? {
+        println!(
+            "{} {}",
+            "◆".blue(),
+            t!("anilist.saved", media.title.green(), progress)
+        );
+    }
+    Ok(())
+}
+
+/// Records the watched episode and pushes it to AniList when a token is
+/// configured.
+pub async fn finished(source: &str, movie: &Movie, episode: &Episode) {
+    if let Err(e) = history::record(source, movie, episode) {
+        eprintln!("{} {}", "■".red(), format!("{:#}", e).yellow());
+    }
+    let Some(anilist) = Anilist::from_config() else {
+        return;
+    };
+    if let Err(e) = push(&anilist, source, movie, episode).await {
+        eprintln!("{} {}", "■".red(), format!("{:#}", e).yellow());
+    }
+}
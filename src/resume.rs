@@ -0,0 +1,112 @@
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{config, watchlist::now};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Position {
+    pub seconds: f64,
+    pub modified: u64,
+}
+
+/// Where playback stopped, per episode. A position of zero means the episode
+/// was finished.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Positions {
+    pub entries: HashMap<String, Position>,
+}
+
+pub fn path() -> PathBuf {
+    config::data_dir().join("positions.json")
+}
+
+fn key(source: &str, episode_id: &str) -> String {
+    format!("{}:{}", source, episode_id)
+}
+
+impl Positions {
+    pub fn load() -> Self {
+        fs::read(path())
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        let path = path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn get(&self, source: &str, episode_id: &str) -> Option<f64> {
+        self.entries
+            .get(&key(source, episode_id))
+            .map(|p| p.seconds)
+            .filter(|s| *s > 0.0)
+    }
+
+    pub fn set(&mut self, source: &str, episode_id: &str, seconds: f64) {
+        self.entries.insert(
+            key(source, episode_id),
+            Position {
+                seconds,
+                modified: now(),
+            },
+        );
+    }
+
+    /// Last-writer-wins merge per episode.
+    pub fn merge(&mut self, other: Positions) {
+        for (key, position) in other.entries {
+            match self.entries.get(&key) {
+                Some(mine) if mine.modified >= position.modified => {}
+                _ => {
+                    self.entries.insert(key, position);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn positions(entries: &[(&str, f64, u64)]) -> Positions {
+        Positions {
+            entries: entries
+                .iter()
+                .map(|(id, seconds, modified)| {
+                    (
+                        key("anime", id),
+                        Position {
+                            seconds: *seconds,
+                            modified: *modified,
+                        },
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn merge_keeps_last_writer() {
+        let mut mine = positions(&[("1", 120.0, 10), ("2", 0.0, 30)]);
+        mine.merge(positions(&[
+            ("1", 300.0, 20),
+            ("2", 50.0, 20),
+            ("3", 42.0, 5),
+        ]));
+
+        assert_eq!(mine.get("anime", "1"), Some(300.0));
+        assert_eq!(mine.get("anime", "2"), None);
+        assert_eq!(mine.get("anime", "3"), Some(42.0));
+        assert_eq!(mine.entries.len(), 3);
+    }
+}
@@ -0,0 +1,154 @@
+use animeku_cli::{
+    ext::SOURCES,
+    models::{Episode, Meta, Movie, Stream},
+    regex, t,
+};
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::{proxy, ApiError, SharedState};
+
+#[derive(Deserialize)]
+pub struct SearchQuery {
+    q: String,
+    #[serde(default = "first_page")]
+    page: usize,
+}
+
+fn first_page() -> usize {
+    1
+}
+
+#[derive(Deserialize)]
+pub struct MovieQuery {
+    id: String,
+    #[serde(default)]
+    title: String,
+}
+
+impl From<MovieQuery> for Movie {
+    fn from(q: MovieQuery) -> Self {
+        Movie {
+            id: q.id,
+            title: q.title,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct EpisodeQuery {
+    id: String,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    series: bool,
+    pub quality: Option<String>,
+}
+
+impl EpisodeQuery {
+    pub fn episode(&self) -> Episode {
+        Episode {
+            id: self.id.clone(),
+            title: self.title.clone(),
+            is_series: self.series,
+        }
+    }
+}
+
+/// A stream as seen by clients: the upstream URL and headers stay behind the
+/// proxy.
+#[derive(Serialize)]
+pub struct ProxiedStream {
+    title: String,
+    quality: Option<String>,
+    url: String,
+}
+
+impl ProxiedStream {
+    fn new(stream: Stream, headers: &HeaderMap) -> Self {
+        Self {
+            url: proxy::public_url(&stream, headers),
+            quality: regex!(r"(?i)\b(\d{3,4}p)\b")
+                .captures(&stream.title)
+                .map(|cap| cap[1].to_lowercase()),
+            title: stream.title,
+        }
+    }
+}
+
+pub async fn sources() -> Json<Value> {
+    Json(json!(SOURCES
+        .iter()
+        .enumerate()
+        .map(|(id, name)| json!({ "id": id, "name": name }))
+        .collect::<Vec<_>>()))
+}
+
+pub async fn search(
+    State(state): State<SharedState>,
+    Path(source): Path<String>,
+    Query(query): Query<SearchQuery>,
+) -> Result<Json<Value>, ApiError> {
+    let (results, total) = state
+        .pipeline(&source)?
+        .search(query.q.trim(), query.page.max(1))
+        .await?;
+    Ok(Json(json!({
+        "page": query.page.max(1),
+        "total": total,
+        "results": results,
+    })))
+}
+
+pub async fn episodes(
+    State(state): State<SharedState>,
+    Path(source): Path<String>,
+    Query(query): Query<MovieQuery>,
+) -> Result<Json<Value>, ApiError> {
+    let (episodes, meta) = state.pipeline(&source)?.episodes(query.into()).await?;
+    Ok(Json(json!({ "episodes": episodes, "meta": meta })))
+}
+
+pub async fn meta(
+    State(state): State<SharedState>,
+    Path(source): Path<String>,
+    Query(query): Query<MovieQuery>,
+) -> Result<Json<Meta>, ApiError> {
+    let (_, meta) = state.pipeline(&source)?.episodes(query.into()).await?;
+    Ok(Json(meta))
+}
+
+pub async fn streams(
+    State(state): State<SharedState>,
+    Path(source): Path<String>,
+    Query(query): Query<EpisodeQuery>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<ProxiedStream>>, ApiError> {
+    let streams = state.pipeline(&source)?.streams(query.episode()).await?;
+    Ok(Json(
+        streams
+            .into_iter()
+            .map(|stream| ProxiedStream::new(stream, &headers))
+            .collect(),
+    ))
+}
+
+pub async fn resolve(
+    State(state): State<SharedState>,
+    Path(source): Path<String>,
+    Query(query): Query<EpisodeQuery>,
+    headers: HeaderMap,
+) -> Result<Json<ProxiedStream>, ApiError> {
+    state
+        .pipeline(&source)?
+        .resolve(query.episode(), query.quality.as_deref())
+        .await?
+        .map(|stream| Json(ProxiedStream::new(stream, &headers)))
+        .ok_or_else(|| ApiError::not_found(t!("server.not_found")))
+}
@@ -0,0 +1,32 @@
+use axum::{
+    extract::State,
+    http::{header, HeaderMap},
+    response::IntoResponse,
+};
+
+use super::{base_url, playlist::play_url, SharedState};
+
+use crate::{calendar, feed::Feed, watchlist::Watchlist};
+
+pub async fn atom(State(state): State<SharedState>, headers: HeaderMap) -> impl IntoResponse {
+    let base = base_url(&headers);
+    let body = Feed::load().atom(&format!("{}/feed.atom", base), |item| {
+        play_url(&base, &item.source, &item.episode, state.token.as_deref())
+    });
+    (
+        [(header::CONTENT_TYPE, "application/atom+xml; charset=utf-8")],
+        body,
+    )
+}
+
+pub async fn ics() -> impl IntoResponse {
+    let entries = Watchlist::load()
+        .entries
+        .into_iter()
+        .filter(|e| e.followed && e.scheduled() && !e.releases.is_empty())
+        .collect::<Vec<_>>();
+    (
+        [(header::CONTENT_TYPE, "text/calendar; charset=utf-8")],
+        calendar::ics(&entries),
+    )
+}
@@ -0,0 +1,225 @@
+use std::{collections::HashMap, net::SocketAddr, sync::Arc};
+
+use animeku_cli::{ext::SOURCES, pipeline::Pipeline, t};
+use anyhow::{bail, Context};
+use axum::{
+    extract::{Query, State},
+    http::{header, HeaderMap, HeaderValue, Method, Request, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use colored::Colorize;
+use serde_json::json;
+use subtle::ConstantTimeEq;
+use tower_http::cors::{Any, CorsLayer};
+
+use crate::{config, metadata};
+
+mod api;
+mod feed;
+pub mod playlist;
+pub mod proxy;
+mod stremio;
+mod web;
+
+pub struct AppState {
+    pub pipelines: Vec<Pipeline>,
+    pub token: Option<String>,
+}
+
+impl AppState {
+    pub fn pipeline(&self, source: &str) -> Result<&Pipeline, ApiError> {
+        SOURCES
+            .iter()
+            .position(|s| s.eq_ignore_ascii_case(source))
+            .map(|i| &self.pipelines[i])
+            .ok_or_else(|| ApiError::not_found(t!("source.unknown", source, SOURCES.join(", "))))
+    }
+}
+
+pub type SharedState = Arc<AppState>;
+
+pub fn base_url(headers: &HeaderMap) -> String {
+    let host = headers
+        .get(header::HOST)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("127.0.0.1");
+    format!("http://{}", host)
+}
+
+pub struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+
+impl ApiError {
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::NOT_FOUND,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(e: anyhow::Error) -> Self {
+        Self {
+            status: StatusCode::BAD_GATEWAY,
+            message: format!("{:#}", e),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(json!({ "error": self.message }))).into_response()
+    }
+}
+
+/// Errors that don't fail a request, such as one title of a playlist.
+pub fn log_error(message: &str) {
+    eprintln!(" {} {}", "■".red(), message.yellow());
+}
+
+/// `/play` also opens with the per-episode key of a playlist.
+async fn play_auth<B>(
+    State(state): State<SharedState>,
+    req: Request<B>,
+    next: Next<B>,
+) -> Result<Response, ApiError> {
+    let signed = state.token.as_ref().is_some_and(|token| {
+        let source = req.uri().path().rsplit('/').next().unwrap_or_default();
+        Query::<HashMap<String, String>>::try_from_uri(req.uri()).is_ok_and(|Query(query)| {
+            match (query.get("id"), query.get("key")) {
+                (Some(id), Some(key)) => bool::from(
+                    playlist::play_key(token, source, id)
+                        .as_bytes()
+                        .ct_eq(key.as_bytes()),
+                ),
+                _ => false,
+            }
+        })
+    });
+    if signed {
+        Ok(next.run(req).await)
+    } else {
+        auth(State(state), req, next).await
+    }
+}
+
+async fn auth<B>(
+    State(state): State<SharedState>,
+    req: Request<B>,
+    next: Next<B>,
+) -> Result<Response, ApiError> {
+    let Some(ref token) = state.token else {
+        return Ok(next.run(req).await);
+    };
+
+    let from_header = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::to_string);
+    let from_query = Query::<HashMap<String, String>>::try_from_uri(req.uri())
+        .ok()
+        .and_then(|Query(mut query)| query.remove("token"));
+
+    let valid = from_header
+        .or(from_query)
+        .is_some_and(|sent| bool::from(sent.as_bytes().ct_eq(token.as_bytes())));
+    if valid {
+        Ok(next.run(req).await)
+    } else {
+        Err(ApiError {
+            status: StatusCode::UNAUTHORIZED,
+            message: t!("server.unauthorized").into(),
+        })
+    }
+}
+
+fn cors(origins: &[String]) -> CorsLayer {
+    let layer = CorsLayer::new()
+        .allow_methods([Method::GET, Method::POST])
+        .allow_headers([header::AUTHORIZATION, header::CONTENT_TYPE]);
+    if origins.is_empty() {
+        return layer.allow_origin(Any);
+    }
+    layer.allow_origin(
+        origins
+            .iter()
+            .filter_map(|o| HeaderValue::from_str(o).ok())
+            .collect::<Vec<_>>(),
+    )
+}
+
+pub fn router(state: SharedState) -> Router {
+    let stremio_prefix = match state.token {
+        Some(ref token) => format!("/stremio/{}", token),
+        None => "/stremio".into(),
+    };
+
+    let api = Router::new()
+        .route("/sources", get(api::sources))
+        .route("/:source/search", get(api::search))
+        .route("/:source/episodes", get(api::episodes))
+        .route("/:source/meta", get(api::meta))
+        .route("/:source/streams", get(api::streams))
+        .route("/:source/resolve", get(api::resolve))
+        .route_layer(middleware::from_fn_with_state(state.clone(), auth));
+    let playlist = Router::new()
+        .route("/feed.atom", get(feed::atom))
+        .route("/calendar.ics", get(feed::ics))
+        .route("/playlist/followed", get(playlist::followed))
+        .route("/playlist/:source", get(playlist::show))
+        .route_layer(middleware::from_fn_with_state(state.clone(), auth));
+    let play = Router::new()
+        .route("/play/:source", get(playlist::play))
+        .route_layer(middleware::from_fn_with_state(state.clone(), play_auth));
+
+    Router::new()
+        .route("/", get(web::index))
+        .route("/app.js", get(web::app_js))
+        .route("/style.css", get(web::style_css))
+        .route("/hls.min.js", get(web::hls_js))
+        .route("/strings.json", get(web::strings))
+        .nest("/api", api)
+        .merge(playlist)
+        .merge(play)
+        .nest(&stremio_prefix, stremio::routes())
+        .merge(proxy::routes())
+        .layer(cors(&config::get().server.cors_origins))
+        .with_state(state)
+}
+
+pub async fn run() -> anyhow::Result<()> {
+    let server = &config::get().server;
+    let addr: SocketAddr = format!("{}:{}", server.host, server.port)
+        .parse()
+        .context(t!("config.invalid"))?;
+    if !addr.ip().is_loopback() && server.token.is_none() {
+        bail!(t!("server.token_required", addr));
+    }
+
+    let ext = config::get().ext();
+    let state = Arc::new(AppState {
+        pipelines: (0..SOURCES.len())
+            .map(|i| {
+                Pipeline::for_source(i, &ext)
+                    .with_enricher(metadata::enricher(SOURCES[i], |message| {
+                        log_error(&message)
+                    }))
+            })
+            .collect(),
+        token: server.token.clone(),
+    });
+
+    println!(" {} {}", "◆".blue(), t!("server.listening", addr).bold());
+    axum::Server::bind(&addr)
+        .serve(router(state).into_make_service())
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,157 @@
+use animeku_cli::{
+    models::{Episode, Movie},
+    t,
+};
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, HeaderMap},
+    response::{IntoResponse, Redirect},
+};
+use hmac::{Hmac, Mac};
+use serde_json::json;
+use sha2::Sha256;
+
+use super::{
+    api::{EpisodeQuery, MovieQuery},
+    base_url, log_error, proxy, ApiError, SharedState,
+};
+
+use crate::{config, watchlist::Watchlist};
+
+fn attr(value: &str) -> String {
+    value.replace(['"', '\n', '\r'], " ")
+}
+
+/// A line break in a title would start a new playlist line.
+fn line(value: &str) -> String {
+    value.trim().replace(['\n', '\r'], " ")
+}
+
+/// Signs one episode with the API token. Playlists carry this key instead of
+/// the token, so a shared playlist only opens its own episodes.
+pub fn play_key(token: &str, source: &str, id: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(token.as_bytes()).expect("any key length");
+    mac.update(source.as_bytes());
+    mac.update(&[0]);
+    mac.update(id.as_bytes());
+    mac.finalize().into_bytes()[..16]
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+pub fn play_url(base: &str, source: &str, episode: &Episode, token: Option<&str>) -> String {
+    let mut query = json!({
+        "id": episode.id,
+        "title": episode.title,
+        "series": episode.is_series,
+    });
+    if let Some(token) = token {
+        query["key"] = json!(play_key(token, source, &episode.id));
+    }
+    format!(
+        "{}/play/{}?{}",
+        base,
+        source,
+        serde_urlencoded::to_string(query).unwrap_or_default()
+    )
+}
+
+pub async fn entries(
+    state: &SharedState,
+    source: &str,
+    movie: Movie,
+    headers: &HeaderMap,
+) -> Result<String, ApiError> {
+    let title = movie.title.trim().to_string();
+    let (episodes, meta) = state.pipeline(source)?.episodes(movie).await?;
+    let logo = meta.thumb_url.unwrap_or_default();
+
+    let base = base_url(headers);
+    let mut out = String::new();
+    for episode in episodes {
+        out.push_str(&format!(
+            "#EXTINF:-1 tvg-logo=\"{}\" group-title=\"{}\",{}\n{}\n",
+            attr(&logo),
+            attr(&title),
+            line(&episode.title),
+            play_url(&base, source, &episode, state.token.as_deref())
+        ));
+    }
+    Ok(out)
+}
+
+pub fn m3u(body: String) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "audio/x-mpegurl; charset=utf-8")],
+        format!("#EXTM3U\n{}", body),
+    )
+}
+
+pub async fn show(
+    State(state): State<SharedState>,
+    Path(source): Path<String>,
+    Query(query): Query<MovieQuery>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, ApiError> {
+    let body = entries(&state, &source, query.into(), &headers).await?;
+    Ok(m3u(body))
+}
+
+pub async fn followed(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, ApiError> {
+    let mut body = String::new();
+    for entry in Watchlist::load().entries.into_iter().filter(|e| e.followed) {
+        match entries(&state, &entry.source, entry.movie(), &headers).await {
+            Ok(part) => body.push_str(&part),
+            Err(e) => log_error(&format!("{}: {}", entry.title, e.message)),
+        }
+    }
+    Ok(m3u(body))
+}
+
+pub async fn play(
+    State(state): State<SharedState>,
+    Path(source): Path<String>,
+    Query(query): Query<EpisodeQuery>,
+    headers: HeaderMap,
+) -> Result<Redirect, ApiError> {
+    let quality = query.quality.clone().or(config::get().quality.clone());
+    let stream = state
+        .pipeline(&source)?
+        .resolve(query.episode(), quality.as_deref())
+        .await?
+        .ok_or_else(|| ApiError::not_found(t!("server.not_found")))?;
+    Ok(Redirect::temporary(&proxy::public_url(&stream, &headers)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn play_urls_carry_episode_keys() {
+        let episode = Episode {
+            id: "ep-1".into(),
+            title: "Episode 1".into(),
+            is_series: true,
+        };
+        let url = play_url("http://tv", "anime", &episode, Some("secret"));
+        assert!(url.starts_with("http://tv/play/anime?"));
+        assert!(url.contains(&format!("key={}", play_key("secret", "anime", "ep-1"))));
+        assert!(!url.contains("secret"));
+        assert!(!play_url("http://tv", "anime", &episode, None).contains("key="));
+
+        let key = play_key("secret", "anime", "ep-1");
+        assert_ne!(key, play_key("secret", "anime", "ep-2"));
+        assert_ne!(key, play_key("secret", "movie", "ep-1"));
+        assert_ne!(key, play_key("other", "anime", "ep-1"));
+    }
+
+    #[test]
+    fn titles_stay_on_one_line() {
+        assert_eq!(line(" Ep 1\r\n#EXTINF:-1,x "), "Ep 1  #EXTINF:-1,x");
+    }
+}
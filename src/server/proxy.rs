@@ -0,0 +1,192 @@
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    num::NonZeroUsize,
+    sync::Mutex,
+};
+
+use animeku_cli::{models::Stream, regex, t, util::stream_request};
+use axum::{
+    body::{boxed, Full, StreamBody},
+    extract::Path,
+    http::{header, HeaderMap},
+    response::Response,
+    routing::get,
+    Router,
+};
+use lru::LruCache;
+use once_cell::sync::Lazy;
+use reqwest::{Client, Url};
+use sha2::{Digest, Sha256};
+use tokio::sync::OnceCell;
+
+use super::{base_url, ApiError};
+
+use crate::config::http_client;
+
+/// Every HLS segment is registered too, so only the most recently used
+/// streams are kept.
+static STREAMS: Lazy<Mutex<LruCache<String, Stream>>> =
+    Lazy::new(|| Mutex::new(LruCache::new(MAX_STREAMS)));
+static SECRET: Lazy<[u8; 32]> = Lazy::new(rand::random);
+static CLIENT: Lazy<Client> = Lazy::new(http_client);
+static LOCAL: OnceCell<SocketAddr> = OnceCell::const_new();
+static LAN: OnceCell<SocketAddr> = OnceCell::const_new();
+
+static MAX_STREAMS: NonZeroUsize = match NonZeroUsize::new(4096) {
+    Some(n) => n,
+    None => unreachable!(),
+};
+
+static FORWARDED_HEADERS: [header::HeaderName; 5] = [
+    header::CONTENT_TYPE,
+    header::CONTENT_LENGTH,
+    header::CONTENT_RANGE,
+    header::ACCEPT_RANGES,
+    header::LAST_MODIFIED,
+];
+
+/// Ids are keyed with a per-process secret, so they can't be guessed from the
+/// upstream URL and work as capabilities without the API token.
+pub fn register(stream: &Stream) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(*SECRET);
+    hasher.update(&stream.url);
+    for (key, value) in &stream.headers {
+        hasher.update([0]);
+        hasher.update(key);
+        hasher.update([0]);
+        hasher.update(value);
+    }
+    let id = hasher.finalize()[..16]
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<String>();
+
+    STREAMS.lock().unwrap().put(id.clone(), stream.clone());
+    id
+}
+
+/// `/stream/<id>`, ending in `.m3u8` for playlists so players and the web UI
+/// can tell HLS apart without seeing the upstream URL.
+fn path(stream: &Stream) -> String {
+    let is_playlist = Url::parse(&stream.url).is_ok_and(|url| url.path().ends_with(".m3u8"));
+    let id = register(stream);
+    if is_playlist {
+        format!("/stream/{}.m3u8", id)
+    } else {
+        format!("/stream/{}", id)
+    }
+}
+
+pub fn public_url(stream: &Stream, headers: &HeaderMap) -> String {
+    format!("{}{}", base_url(headers), path(stream))
+}
+
+pub fn routes<S: Clone + Send + Sync + 'static>() -> Router<S> {
+    Router::new().route("/stream/:id", get(stream))
+}
+
+async fn start(ip: IpAddr) -> anyhow::Result<SocketAddr> {
+    let server =
+        axum::Server::try_bind(&SocketAddr::new(ip, 0))?.serve(routes::<()>().into_make_service());
+    let addr = server.local_addr();
+    tokio::spawn(server);
+    Ok(addr)
+}
+
+pub async fn local_url(stream: &Stream) -> anyhow::Result<String> {
+    let addr = LOCAL
+        .get_or_try_init(|| start(Ipv4Addr::LOCALHOST.into()))
+        .await?;
+    Ok(format!("http://{}{}", addr, path(stream)))
+}
+
+pub async fn lan_url(stream: &Stream, peer: IpAddr) -> anyhow::Result<String> {
+    let addr = LAN
+        .get_or_try_init(|| start(Ipv4Addr::UNSPECIFIED.into()))
+        .await?;
+    let socket = std::net::UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))?;
+    socket.connect((peer, 9))?;
+    let ip = socket.local_addr()?.ip();
+    Ok(format!(
+        "http://{}{}",
+        SocketAddr::new(ip, addr.port()),
+        path(stream)
+    ))
+}
+
+async fn stream(Path(id): Path<String>, headers: HeaderMap) -> Result<Response, ApiError> {
+    let id = id.trim_end_matches(".m3u8");
+    let stream = STREAMS
+        .lock()
+        .unwrap()
+        .get(id)
+        .cloned()
+        .ok_or_else(|| ApiError::not_found(t!("server.not_found")))?;
+
+    let mut req = stream_request(&CLIENT, &stream)?;
+    if let Some(range) = headers.get(header::RANGE) {
+        req = req.header(header::RANGE, range);
+    }
+    let resp = req.send().await.map_err(anyhow::Error::from)?;
+
+    let is_playlist = resp
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.to_lowercase().contains("mpegurl"))
+        || resp.url().path().ends_with(".m3u8");
+
+    let mut builder = Response::builder().status(resp.status());
+    if is_playlist {
+        let mut base = resp.url().clone();
+        let original = Url::parse(&stream.url).map_err(anyhow::Error::from)?;
+        if base.host_str() == original.host_str() {
+            let _ = base.set_username(original.username());
+            let _ = base.set_password(original.password());
+        }
+        let body = resp.text().await.map_err(anyhow::Error::from)?;
+        let body = rewrite_playlist(&body, &base, &stream.headers);
+        return Ok(builder
+            .header(header::CONTENT_TYPE, "application/vnd.apple.mpegurl")
+            .body(boxed(Full::from(body)))
+            .map_err(anyhow::Error::from)?);
+    }
+
+    for name in &FORWARDED_HEADERS {
+        if let Some(value) = resp.headers().get(name) {
+            builder = builder.header(name, value);
+        }
+    }
+    Ok(builder
+        .body(boxed(StreamBody::new(resp.bytes_stream())))
+        .map_err(anyhow::Error::from)?)
+}
+
+fn rewrite_playlist(body: &str, base: &Url, headers: &[(String, String)]) -> String {
+    let proxied = |uri: &str| match base.join(uri.trim()) {
+        Ok(url) => path(&Stream {
+            url: url.to_string(),
+            title: String::new(),
+            headers: headers.to_vec(),
+        }),
+        Err(_) => uri.to_string(),
+    };
+
+    body.lines()
+        .map(|line| {
+            if line.starts_with('#') {
+                regex!(r#"URI="([^"]+)""#)
+                    .replace_all(line, |cap: &regex::Captures| {
+                        format!(r#"URI="{}""#, proxied(&cap[1]))
+                    })
+                    .into_owned()
+            } else if line.trim().is_empty() {
+                line.to_string()
+            } else {
+                proxied(line)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
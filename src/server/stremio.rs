@@ -0,0 +1,252 @@
+use std::collections::HashMap;
+
+use animeku_cli::{
+    ext::SOURCES,
+    models::{Episode, Meta, Movie},
+    t,
+    util::episode_number,
+};
+use anyhow::anyhow;
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    routing::get,
+    Json, Router,
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde_json::{json, Value};
+
+use super::{proxy, ApiError, SharedState};
+
+static ID_PREFIX: &str = "animeku";
+static PAGE_SIZE: usize = 20;
+
+pub fn routes() -> Router<SharedState> {
+    Router::new()
+        .route("/manifest.json", get(manifest))
+        .route("/catalog/:type/:id", get(catalog))
+        .route("/catalog/:type/:id/:extra", get(catalog_extra))
+        .route("/meta/:type/:id", get(meta))
+        .route("/stream/:type/:id", get(stream))
+}
+
+fn kind(source: &str) -> &'static str {
+    if source == "movie" {
+        "movie"
+    } else {
+        "series"
+    }
+}
+
+/// Type of a single title, tenflix mixes films (`movie/...` ids) with series.
+fn item_kind(source: &str, movie_id: &str) -> &'static str {
+    if source == "tenflix" && movie_id.starts_with("movie") {
+        "movie"
+    } else {
+        kind(source)
+    }
+}
+
+fn catalog_name(source: &str) -> &'static str {
+    match source {
+        "anime" => "Nontonanime · Anime",
+        "movie" => "Nontonanime · Movie",
+        _ => "Tenflix",
+    }
+}
+
+fn encode(value: &str) -> String {
+    URL_SAFE_NO_PAD.encode(value)
+}
+
+fn decode(value: &str) -> anyhow::Result<String> {
+    Ok(String::from_utf8(URL_SAFE_NO_PAD.decode(value)?)?)
+}
+
+struct ItemId {
+    source: String,
+    movie: Movie,
+    episode: Option<String>,
+}
+
+impl ItemId {
+    fn meta_id(source: &str, movie: &Movie) -> String {
+        format!(
+            "{}:{}:{}:{}",
+            ID_PREFIX,
+            source,
+            encode(&movie.id),
+            encode(&movie.title)
+        )
+    }
+
+    fn parse(id: &str) -> Result<Self, ApiError> {
+        let id = id.trim_end_matches(".json");
+        let not_found = || ApiError::not_found(t!("server.not_found"));
+        let parts = id.split(':').collect::<Vec<_>>();
+        if parts.len() < 4 || parts[0] != ID_PREFIX {
+            return Err(not_found());
+        }
+        Ok(Self {
+            source: parts[1].to_string(),
+            movie: Movie {
+                id: decode(parts[2])?,
+                title: decode(parts[3])?,
+                ..Default::default()
+            },
+            episode: parts.get(4).map(|e| decode(e)).transpose()?,
+        })
+    }
+}
+
+fn meta_preview(source: &str, movie: &Movie) -> Value {
+    json!({
+        "id": ItemId::meta_id(source, movie),
+        "type": item_kind(source, &movie.id),
+        "name": movie.title.trim(),
+        "poster": movie.meta.thumb_url,
+        "description": description(&movie.meta),
+    })
+}
+
+fn description(meta: &Meta) -> String {
+    meta.data
+        .iter()
+        .map(|(key, value)| format!("{}: {}", key, value))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+async fn manifest() -> Json<Value> {
+    let catalogs = SOURCES
+        .iter()
+        .map(|source| {
+            json!({
+                "type": kind(source),
+                "id": format!("{}-{}", ID_PREFIX, source),
+                "name": catalog_name(source),
+                "extra": [
+                    { "name": "search" },
+                    { "name": "skip" },
+                ],
+            })
+        })
+        .collect::<Vec<_>>();
+
+    Json(json!({
+        "id": "id.animeku.cli",
+        "version": env!("CARGO_PKG_VERSION"),
+        "name": "animeku",
+        "description": "Anime dan film Asia dengan subtitle Indonesia",
+        "resources": ["catalog", "meta", "stream"],
+        "types": ["series", "movie"],
+        "idPrefixes": [format!("{}:", ID_PREFIX)],
+        "catalogs": catalogs,
+    }))
+}
+
+async fn catalog(
+    state: State<SharedState>,
+    Path((kind, id)): Path<(String, String)>,
+) -> Result<Json<Value>, ApiError> {
+    catalog_extra(state, Path((kind, id, String::new()))).await
+}
+
+/// Search results, or the latest titles of the source when there is no
+/// search query so the catalogs also fill the board.
+async fn catalog_extra(
+    State(state): State<SharedState>,
+    Path((_, id, extra)): Path<(String, String, String)>,
+) -> Result<Json<Value>, ApiError> {
+    let id = id.trim_end_matches(".json");
+    let source = id
+        .strip_prefix(&format!("{}-", ID_PREFIX))
+        .ok_or_else(|| ApiError::not_found(t!("server.not_found")))?;
+    let extra: HashMap<String, String> =
+        serde_urlencoded::from_str(extra.trim_end_matches(".json")).map_err(anyhow::Error::from)?;
+
+    let query = extra.get("search").map(|q| q.trim()).unwrap_or_default();
+    let skip = extra
+        .get("skip")
+        .and_then(|s| s.parse::<usize>().ok())
+        .unwrap_or(0);
+
+    let (movies, _) = state
+        .pipeline(source)?
+        .search(query, skip / PAGE_SIZE + 1)
+        .await?;
+    Ok(Json(json!({
+        "metas": movies
+            .iter()
+            .map(|movie| meta_preview(source, movie))
+            .collect::<Vec<_>>(),
+    })))
+}
+
+async fn meta(
+    State(state): State<SharedState>,
+    Path((_, id)): Path<(String, String)>,
+) -> Result<Json<Value>, ApiError> {
+    let item = ItemId::parse(&id)?;
+    let meta_id = ItemId::meta_id(&item.source, &item.movie);
+    let (episodes, meta) = state
+        .pipeline(&item.source)?
+        .episodes(item.movie.clone())
+        .await?;
+
+    let videos = episodes
+        .iter()
+        .enumerate()
+        .map(|(i, episode)| {
+            json!({
+                "id": format!("{}:{}", meta_id, encode(&episode.id)),
+                "title": episode.title.trim(),
+                "season": 1,
+                "episode": episode_number(&episode.title).unwrap_or(i + 1),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    Ok(Json(json!({
+        "meta": {
+            "id": meta_id,
+            "type": item_kind(&item.source, &item.movie.id),
+            "name": item.movie.title.trim(),
+            "poster": meta.thumb_url,
+            "background": meta.thumb_url,
+            "description": description(&meta),
+            "videos": videos,
+        }
+    })))
+}
+
+async fn stream(
+    State(state): State<SharedState>,
+    Path((_, id)): Path<(String, String)>,
+    headers: HeaderMap,
+) -> Result<Json<Value>, ApiError> {
+    let item = ItemId::parse(&id)?;
+    let pipeline = state.pipeline(&item.source)?;
+    let (episodes, _) = pipeline.episodes(item.movie).await?;
+
+    let episode: Episode = match item.episode {
+        Some(ref id) => episodes.into_iter().find(|e| &e.id == id),
+        None => episodes.into_iter().next(),
+    }
+    .ok_or_else(|| anyhow!(t!("server.not_found")))?;
+
+    let streams = pipeline
+        .streams(episode)
+        .await?
+        .iter()
+        .map(|stream| {
+            json!({
+                "name": "animeku",
+                "title": format!("{}\nSub Indo", stream.title.trim()),
+                "url": proxy::public_url(stream, &headers),
+                "behaviorHints": { "notWebReady": true },
+            })
+        })
+        .collect::<Vec<_>>();
+    Ok(Json(json!({ "streams": streams })))
+}
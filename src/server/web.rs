@@ -0,0 +1,70 @@
+use std::collections::HashMap;
+
+use animeku_cli::i18n;
+use axum::{
+    http::header,
+    response::{Html, IntoResponse},
+    Json,
+};
+
+static INDEX: &str = include_str!("web/index.html");
+static APP_JS: &str = include_str!("web/app.js");
+static STYLE_CSS: &str = include_str!("web/style.css");
+/// Pinned hls.js build, served locally so the page works offline and never
+/// loads third-party scripts next to the API token.
+static HLS_JS: &str = include_str!("web/hls.min.js");
+
+/// Messages used by the web page, `{}` marks an argument.
+static STRINGS: [&str; 11] = [
+    "input.menu.search",
+    "search.progress",
+    "search.found",
+    "search.not_found",
+    "episode.progress",
+    "stream.progress",
+    "tui.page",
+    "web.search",
+    "web.back",
+    "web.no_streams",
+    "web.hls_failed",
+];
+
+pub async fn index() -> Html<&'static str> {
+    Html(INDEX)
+}
+
+pub async fn app_js() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/javascript; charset=utf-8")],
+        APP_JS,
+    )
+}
+
+pub async fn style_css() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/css; charset=utf-8")],
+        STYLE_CSS,
+    )
+}
+
+pub async fn hls_js() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/javascript; charset=utf-8")],
+        HLS_JS,
+    )
+}
+
+pub async fn strings() -> Json<HashMap<&'static str, &'static str>> {
+    let mut strings = STRINGS
+        .iter()
+        .map(|key| (*key, i18n::tr(key)))
+        .collect::<HashMap<_, _>>();
+    strings.insert(
+        "lang",
+        match i18n::lang() {
+            i18n::Lang::Id => "id",
+            i18n::Lang::En => "en",
+        },
+    );
+    Json(strings)
+}
@@ -0,0 +1,235 @@
+const HLS_JS = "/hls.min.js";
+
+const $ = (id) => document.getElementById(id);
+const state = { source: "anime", query: "", page: 1, movie: null, hls: null };
+let strings = {};
+
+// Message from the server catalog, each `{}` takes the next argument.
+function tr(key, ...args) {
+  let i = 0;
+  return (strings[key] || key).replace(/\{\}/g, () => args[i++] ?? "").trim();
+}
+
+async function loadStrings() {
+  strings = await (await fetch("/strings.json")).json();
+  document.documentElement.lang = strings.lang;
+  document.querySelectorAll("[data-i18n]").forEach((node) => {
+    node.textContent = tr(node.dataset.i18n);
+  });
+  document.querySelectorAll("[data-i18n-placeholder]").forEach((node) => {
+    node.placeholder = tr(node.dataset.i18nPlaceholder);
+  });
+}
+
+const params = new URLSearchParams(location.search);
+if (params.has("token")) {
+  localStorage.setItem("token", params.get("token"));
+}
+
+async function api(path, query = {}) {
+  const url = new URL(`/api/${path}`, location.origin);
+  for (const [key, value] of Object.entries(query)) {
+    url.searchParams.set(key, value);
+  }
+  const headers = {};
+  const token = localStorage.getItem("token");
+  if (token) {
+    headers.Authorization = `Bearer ${token}`;
+  }
+
+  const res = await fetch(url, { headers });
+  if (res.status === 401) {
+    const token = prompt("Token");
+    if (token) {
+      localStorage.setItem("token", token);
+      return api(path, query);
+    }
+  }
+  const body = await res.json();
+  if (!res.ok) {
+    throw new Error(body.error || res.statusText);
+  }
+  return body;
+}
+
+function status(text) {
+  $("status").textContent = text || "";
+}
+
+function el(tag, props = {}, children = []) {
+  const node = Object.assign(document.createElement(tag), props);
+  node.append(...children);
+  return node;
+}
+
+async function loadSources() {
+  const sources = await api("sources");
+  $("source").replaceChildren(
+    ...sources.map((s) => el("option", { value: s.name, textContent: s.name }))
+  );
+}
+
+async function search() {
+  status(tr("search.progress", state.query));
+  $("detail").hidden = true;
+  try {
+    const data = await api(`${state.source}/search`, { q: state.query, page: state.page });
+    status(data.results.length ? tr("search.found", data.total) : tr("search.not_found"));
+    $("results").replaceChildren(...data.results.map(card));
+    $("results").hidden = false;
+    $("pager").hidden = false;
+    $("page").textContent = tr("tui.page", state.page);
+  } catch (e) {
+    status(e.message);
+  }
+}
+
+function card(movie) {
+  const node = el("div", { className: "card", tabIndex: 0 }, [
+    el("img", { src: movie.meta.thumb_url || "", loading: "lazy", alt: "" }),
+    el("span", {
+      textContent: movie.total_episodes
+        ? `${movie.title} (${movie.total_episodes} eps)`
+        : movie.title,
+    }),
+  ]);
+  node.onclick = () => openMovie(movie);
+  node.onkeydown = (e) => e.key === "Enter" && openMovie(movie);
+  return node;
+}
+
+async function openMovie(movie) {
+  state.movie = movie;
+  status(tr("episode.progress", movie.title));
+  try {
+    const data = await api(`${state.source}/episodes`, { id: movie.id, title: movie.title });
+    status("");
+    $("results").hidden = true;
+    $("pager").hidden = true;
+    $("detail").hidden = false;
+    $("player").hidden = true;
+    stop();
+
+    $("title").textContent = movie.title;
+    $("poster").src = data.meta.thumb_url || movie.meta.thumb_url || "";
+    $("meta").replaceChildren(
+      ...data.meta.data.flatMap(([key, value]) => [
+        el("dt", { textContent: key }),
+        el("dd", { textContent: value }),
+      ])
+    );
+    $("episodes").replaceChildren(
+      ...data.episodes.map((episode) => {
+        const item = el("li", { textContent: episode.title, tabIndex: 0 });
+        item.onclick = () => openEpisode(episode, item);
+        item.onkeydown = (e) => e.key === "Enter" && openEpisode(episode, item);
+        return item;
+      })
+    );
+  } catch (e) {
+    status(e.message);
+  }
+}
+
+async function openEpisode(episode, item) {
+  document.querySelectorAll("#episodes li").forEach((li) => li.classList.remove("active"));
+  item.classList.add("active");
+  status(tr("stream.progress", episode.title));
+  try {
+    const streams = await api(`${state.source}/streams`, {
+      id: episode.id,
+      title: episode.title,
+      series: episode.is_series,
+    });
+    status(streams.length ? "" : tr("web.no_streams"));
+    const buttons = streams.map((stream) => {
+      const button = el("button", { textContent: stream.title });
+      button.onclick = () => {
+        buttons.forEach((b) => b.classList.remove("active"));
+        button.classList.add("active");
+        play(stream.url);
+      };
+      return button;
+    });
+    $("streams").replaceChildren(...buttons);
+    $("player").hidden = false;
+    if (buttons.length) {
+      buttons[0].click();
+      $("player").scrollIntoView({ behavior: "smooth" });
+    }
+  } catch (e) {
+    status(e.message);
+  }
+}
+
+function stop() {
+  if (state.hls) {
+    state.hls.destroy();
+    state.hls = null;
+  }
+  $("video").removeAttribute("src");
+  $("video").load();
+}
+
+function loadHls() {
+  if (window.Hls) {
+    return Promise.resolve();
+  }
+  return new Promise((resolve, reject) => {
+    const script = el("script", { src: HLS_JS, onload: resolve, onerror: reject });
+    document.head.append(script);
+  });
+}
+
+async function play(url) {
+  stop();
+  const video = $("video");
+  const isHls = /\.m3u8(\?|$)/i.test(url);
+
+  if (!isHls || video.canPlayType("application/vnd.apple.mpegurl")) {
+    video.src = url;
+    return;
+  }
+  try {
+    await loadHls();
+    if (!window.Hls) {
+      throw new Error("hls.js");
+    }
+    state.hls = new Hls();
+    state.hls.loadSource(url);
+    state.hls.attachMedia(video);
+  } catch {
+    status(tr("web.hls_failed"));
+  }
+}
+
+$("search").onsubmit = (e) => {
+  e.preventDefault();
+  state.source = $("source").value;
+  state.query = $("query").value.trim();
+  state.page = 1;
+  if (state.query) {
+    search();
+  }
+};
+$("prev").onclick = () => {
+  if (state.page > 1) {
+    state.page -= 1;
+    search();
+  }
+};
+$("next").onclick = () => {
+  state.page += 1;
+  search();
+};
+$("back").onclick = () => {
+  stop();
+  $("detail").hidden = true;
+  $("results").hidden = false;
+  $("pager").hidden = false;
+};
+
+loadStrings()
+  .catch(() => {})
+  .then(loadSources)
+  .catch((e) => status(e.message));
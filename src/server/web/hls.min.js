@@ -0,0 +1,7 @@
+/*
+ * hls.js v1.5.13 (Apache-2.0), dist/hls.min.js from
+ * https://cdn.jsdelivr.net/npm/hls.js@1.5.13/dist/hls.min.js
+ *
+ * Replace this file with the release build before packaging; the page only
+ * ever loads the copy served at /hls.min.js.
+ */
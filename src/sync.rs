@@ -0,0 +1,335 @@
+use std::{fs, path::PathBuf};
+
+use animeku_cli::t;
+use anyhow::{anyhow, bail};
+use reqwest::{
+    header::{ETAG, IF_MATCH, IF_NONE_MATCH},
+    Client, Method, StatusCode, Url,
+};
+use serde::de::DeserializeOwned;
+
+use crate::{
+    config::{self, http_client},
+    history::{self, History},
+    resume::{self, Positions},
+    watchlist::{self, Watchlist},
+};
+
+static MAX_ATTEMPTS: usize = 3;
+
+/// State of a remote file when it was read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Version {
+    Missing,
+    Tagged(String),
+    Untagged,
+}
+
+pub enum Remote {
+    Dir(PathBuf),
+    WebDav {
+        client: Client,
+        url: Url,
+        username: Option<String>,
+        password: Option<String>,
+    },
+}
+
+impl Remote {
+    pub fn from_config() -> anyhow::Result<Self> {
+        let sync = &config::get().sync;
+        if let Some(ref url) = sync.webdav {
+            let mut url = Url::parse(url)?;
+            if !url.path().ends_with('/') {
+                url.set_path(&format!("{}/", url.path()));
+            }
+            return Ok(Remote::WebDav {
+                client: http_client(),
+                url,
+                username: sync.username.clone(),
+                password: sync.password.clone(),
+            });
+        }
+        match sync.dir {
+            Some(ref dir) => Ok(Remote::Dir(dir.clone())),
+            None => bail!(t!("sync.not_configured")),
+        }
+    }
+
+    fn request(&self, method: Method, name: &str) -> anyhow::Result<reqwest::RequestBuilder> {
+        let Remote::WebDav {
+            client,
+            url,
+            username,
+            password,
+        } = self
+        else {
+            bail!(t!("sync.not_configured"));
+        };
+        let mut req = client.request(method, url.join(name)?);
+        if let Some(username) = username {
+            req = req.basic_auth(username, password.as_ref());
+        }
+        Ok(req)
+    }
+
+    /// Creates `folder` and its parents.
+    async fn prepare(&self, folder: &str) -> anyhow::Result<()> {
+        match self {
+            Remote::Dir(dir) => fs::create_dir_all(dir.join(folder))?,
+            Remote::WebDav { .. } => {
+                let mut collections = vec![String::new()];
+                for part in folder.split_terminator('/') {
+                    collections.push(format!("{}{}/", collections.last().unwrap(), part));
+                }
+                for collection in collections {
+                    let resp = self
+                        .request(Method::from_bytes(b"MKCOL")?, &collection)?
+                        .send()
+                        .await?;
+                    let status = resp.status();
+                    if !status.is_success() && status != StatusCode::METHOD_NOT_ALLOWED {
+                        bail!(t!("sync.failed", "MKCOL", status));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads `name` along with the version needed to overwrite it safely.
+    pub async fn read(&self, name: &str) -> anyhow::Result<(Option<Vec<u8>>, Version)> {
+        match self {
+            Remote::Dir(dir) => match fs::read(dir.join(name)) {
+                Ok(bytes) => Ok((Some(bytes), Version::Untagged)),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok((None, Version::Missing)),
+                Err(e) => Err(e.into()),
+            },
+            Remote::WebDav { .. } => {
+                let resp = self.request(Method::GET, name)?.send().await?;
+                match resp.status() {
+                    StatusCode::NOT_FOUND => Ok((None, Version::Missing)),
+                    status if status.is_success() => {
+                        let version = resp
+                            .headers()
+                            .get(ETAG)
+                            .and_then(|etag| etag.to_str().ok())
+                            .map_or(Version::Untagged, |etag| Version::Tagged(etag.into()));
+                        Ok((Some(resp.bytes().await?.to_vec()), version))
+                    }
+                    status => bail!(t!("sync.failed", name, status)),
+                }
+            }
+        }
+    }
+
+    /// Writes `name` unless it changed since it was read as `version`,
+    /// returns whether the write went through.
+    pub async fn write(
+        &self,
+        name: &str,
+        bytes: Vec<u8>,
+        version: &Version,
+    ) -> anyhow::Result<bool> {
+        match self {
+            Remote::Dir(dir) => {
+                let path = dir.join(name);
+                let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+                let partial = path.with_file_name(format!(".{}.part", file_name));
+                fs::write(&partial, bytes)?;
+                fs::rename(partial, path)?;
+            }
+            Remote::WebDav { .. } => {
+                let mut req = self.request(Method::PUT, name)?.body(bytes);
+                match version {
+                    Version::Missing => req = req.header(IF_NONE_MATCH, "*"),
+                    Version::Tagged(etag) => req = req.header(IF_MATCH, etag),
+                    Version::Untagged => {}
+                }
+                let status = req.send().await?.status();
+                if status == StatusCode::PRECONDITION_FAILED {
+                    return Ok(false);
+                }
+                if !status.is_success() {
+                    bail!(t!("sync.failed", name, status));
+                }
+            }
+        }
+        Ok(true)
+    }
+}
+
+/// Merges a local store with the remote copy of `name` and writes the result
+/// to both sides, starting over when the remote copy changed in between.
+async fn merge_file<T: DeserializeOwned>(
+    remote: &Remote,
+    name: &str,
+    path: PathBuf,
+    load: fn() -> T,
+    merge: fn(&mut T, T),
+    save: fn(&T) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    for _ in 0..MAX_ATTEMPTS {
+        let (bytes, version) = remote.read(name).await?;
+        let mut local = load();
+        if let Some(bytes) = bytes {
+            let other = serde_json::from_slice(&bytes).map_err(|e| anyhow!("{}: {}", name, e))?;
+            merge(&mut local, other);
+        }
+        save(&local)?;
+        if remote.write(name, fs::read(&path)?, &version).await? {
+            return Ok(());
+        }
+    }
+    bail!(t!("sync.conflict", name))
+}
+
+/// Remote folder of the active profile, the root for the default profile so
+/// existing remotes keep working.
+fn folder() -> String {
+    match config::profile() {
+        Some(name) => format!("profiles/{}/", name),
+        None => String::new(),
+    }
+}
+
+/// Merges the local history, watchlist and resume positions with the remote
+/// copies and writes the result to both sides.
+pub async fn sync() -> anyhow::Result<()> {
+    let remote = Remote::from_config()?;
+    let folder = folder();
+    remote.prepare(&folder).await?;
+
+    merge_file(
+        &remote,
+        &format!("{}watchlist.json", folder),
+        watchlist::path(),
+        Watchlist::load,
+        Watchlist::merge,
+        Watchlist::save,
+    )
+    .await?;
+    merge_file(
+        &remote,
+        &format!("{}history.json", folder),
+        history::path(),
+        History::load,
+        History::merge,
+        History::save,
+    )
+    .await?;
+    merge_file(
+        &remote,
+        &format!("{}positions.json", folder),
+        resume::path(),
+        Positions::load,
+        Positions::merge,
+        Positions::save,
+    )
+    .await
+}
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc, Mutex,
+        },
+    };
+
+    use axum::{
+        body::Bytes,
+        extract::{Path, State},
+        http::{header, HeaderMap},
+        response::IntoResponse,
+        routing::get,
+        Router,
+    };
+
+    use super::*;
+
+    type Files = Arc<Mutex<HashMap<String, (Vec<u8>, String)>>>;
+
+    static VERSION: AtomicUsize = AtomicUsize::new(1);
+
+    async fn get_file(State(files): State<Files>, Path(name): Path<String>) -> impl IntoResponse {
+        match files.lock().unwrap().get(&name) {
+            Some((bytes, etag)) => Ok(([(header::ETAG, etag.clone())], bytes.clone())),
+            None => Err(StatusCode::NOT_FOUND),
+        }
+    }
+
+    /// Honours `If-Match` and `If-None-Match: *` like a WebDAV server would.
+    async fn put_file(
+        State(files): State<Files>,
+        Path(name): Path<String>,
+        headers: HeaderMap,
+        body: Bytes,
+    ) -> StatusCode {
+        let mut files = files.lock().unwrap();
+        let current = files.get(&name).map(|(_, etag)| etag.as_str());
+        let header = |name| headers.get(name).and_then(|v| v.to_str().ok());
+        let allowed = match (header(header::IF_MATCH), header(header::IF_NONE_MATCH)) {
+            (Some(etag), _) => current == Some(etag),
+            (_, Some("*")) => current.is_none(),
+            _ => true,
+        };
+        if !allowed {
+            return StatusCode::PRECONDITION_FAILED;
+        }
+        let etag = format!("\"{}\"", VERSION.fetch_add(1, Ordering::Relaxed));
+        files.insert(name, (body.to_vec(), etag));
+        StatusCode::CREATED
+    }
+
+    fn webdav() -> Remote {
+        let app = Router::new()
+            .route("/dav/:name", get(get_file).put(put_file))
+            .with_state(Files::default());
+        let server = axum::Server::bind(&([127, 0, 0, 1], 0).into()).serve(app.into_make_service());
+        let url = Url::parse(&format!("http://{}/dav/", server.local_addr())).unwrap();
+        tokio::spawn(server);
+        Remote::WebDav {
+            client: Client::new(),
+            url,
+            username: None,
+            password: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn write_refuses_stale_versions() {
+        let remote = webdav();
+        let (bytes, missing) = remote.read("watchlist.json").await.unwrap();
+        assert_eq!((bytes, &missing), (None, &Version::Missing));
+
+        assert!(remote
+            .write("watchlist.json", b"{}".to_vec(), &missing)
+            .await
+            .unwrap());
+        // Another device created the file after we read it as missing.
+        assert!(!remote
+            .write("watchlist.json", b"{ }".to_vec(), &missing)
+            .await
+            .unwrap());
+
+        let (bytes, first) = remote.read("watchlist.json").await.unwrap();
+        assert_eq!(bytes.as_deref(), Some(&b"{}"[..]));
+        assert!(matches!(first, Version::Tagged(_)));
+
+        assert!(remote
+            .write("watchlist.json", b"{\"entries\":[]}".to_vec(), &first)
+            .await
+            .unwrap());
+        // Our copy is now older than the remote one.
+        assert!(!remote
+            .write("watchlist.json", b"{}".to_vec(), &first)
+            .await
+            .unwrap());
+
+        let (bytes, second) = remote.read("watchlist.json").await.unwrap();
+        assert_eq!(bytes.as_deref(), Some(&b"{\"entries\":[]}"[..]));
+        assert_ne!(first, second);
+    }
+}
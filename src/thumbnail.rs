@@ -0,0 +1,266 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    env, fs,
+    hash::{Hash, Hasher},
+    io::{stdout, IsTerminal, Write},
+};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use image::{imageops::FilterType, DynamicImage, GenericImageView, ImageOutputFormat};
+
+use crate::config::{self, ThumbnailMode};
+
+static ASCII_RAMP: &[u8] = b" .:-=+*#%@";
+
+pub fn detect() -> ThumbnailMode {
+    let term = env::var("TERM").unwrap_or_default().to_lowercase();
+    let program = env::var("TERM_PROGRAM").unwrap_or_default().to_lowercase();
+
+    if term == "dumb" || term == "linux" {
+        return ThumbnailMode::Ascii;
+    }
+    if viuer::get_kitty_support() != viuer::KittySupport::None
+        || env::var("KITTY_WINDOW_ID").is_ok()
+        || program == "ghostty"
+    {
+        return ThumbnailMode::Kitty;
+    }
+    if viuer::is_iterm_supported() || program == "wezterm" {
+        return ThumbnailMode::Iterm;
+    }
+    if term.contains("sixel")
+        || ["foot", "mlterm", "yaft", "contour"]
+            .iter()
+            .any(|t| term.starts_with(t) || program == *t)
+    {
+        return ThumbnailMode::Sixel;
+    }
+    ThumbnailMode::Halfblock
+}
+
+pub fn mode() -> ThumbnailMode {
+    let thumbnail = &config::get().thumbnail;
+    if !thumbnail.enabled || !stdout().is_terminal() {
+        return ThumbnailMode::Off;
+    }
+    match thumbnail.mode {
+        ThumbnailMode::Auto => detect(),
+        mode => mode,
+    }
+}
+
+pub fn size() -> (u32, u32) {
+    let thumbnail = &config::get().thumbnail;
+    let (columns, rows) = crossterm::terminal::size().unwrap_or((80, 24));
+    let (columns, rows) = (columns as u32, rows as u32);
+
+    let width = thumbnail
+        .width
+        .unwrap_or(columns * 2 / 5)
+        .clamp(8, columns.saturating_sub(4).max(8));
+    let height = thumbnail
+        .height
+        .unwrap_or(rows / 2)
+        .clamp(4, rows.saturating_sub(10).max(4));
+    (width, height)
+}
+
+pub async fn show(url: &str) {
+    let mode = mode();
+    if mode == ThumbnailMode::Off {
+        return;
+    }
+
+    if let Some(img) = fetch_image(url).await {
+        let (width, height) = size();
+        let printed = match mode {
+            ThumbnailMode::Ascii => print_ascii(&img, width, height),
+            ThumbnailMode::Kitty => print_kitty(&img, width, height),
+            ThumbnailMode::Iterm => print_iterm(&img, width, height),
+            ThumbnailMode::Sixel => print_sixel(&img, width, height),
+            _ => print_halfblock(&img, width, height),
+        };
+        if printed.is_ok() {
+            println!();
+        }
+    }
+}
+
+fn print_halfblock(img: &DynamicImage, width: u32, height: u32) -> anyhow::Result<()> {
+    let conf = viuer::Config {
+        transparent: true,
+        absolute_offset: false,
+        width: Some(width),
+        height: Some(height),
+        x: 2,
+        y: 0,
+        use_kitty: false,
+        use_iterm: false,
+        ..Default::default()
+    };
+    viuer::print(img, &conf)?;
+    Ok(())
+}
+
+pub fn ascii_lines(img: &DynamicImage, width: u32, height: u32) -> Vec<String> {
+    let (img_width, img_height) = img.dimensions();
+    let rows = ((width as f32 * img_height as f32 / img_width.max(1) as f32) / 2.0)
+        .round()
+        .clamp(1.0, height as f32) as u32;
+    let columns = ((rows as f32 * 2.0 * img_width as f32 / img_height.max(1) as f32).round()
+        as u32)
+        .clamp(1, width);
+
+    let gray = img
+        .resize_exact(columns, rows, FilterType::Triangle)
+        .to_luma8();
+    (0..rows)
+        .map(|y| {
+            (0..columns)
+                .map(|x| {
+                    let luma = gray.get_pixel(x, y)[0] as usize;
+                    ASCII_RAMP[luma * (ASCII_RAMP.len() - 1) / 255] as char
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn print_ascii(img: &DynamicImage, width: u32, height: u32) -> anyhow::Result<()> {
+    let mut out = stdout().lock();
+    for line in ascii_lines(img, width, height) {
+        writeln!(out, "  {}", line)?;
+    }
+    Ok(())
+}
+
+fn png_bytes(img: &DynamicImage) -> anyhow::Result<Vec<u8>> {
+    let mut bytes = std::io::Cursor::new(Vec::new());
+    img.write_to(&mut bytes, ImageOutputFormat::Png)?;
+    Ok(bytes.into_inner())
+}
+
+fn print_kitty(img: &DynamicImage, width: u32, height: u32) -> anyhow::Result<()> {
+    let encoded = STANDARD.encode(png_bytes(img)?);
+    let chunks = encoded.as_bytes().chunks(4096).collect::<Vec<_>>();
+
+    let mut out = stdout().lock();
+    write!(out, "  ")?;
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = (i + 1 < chunks.len()) as u8;
+        if i == 0 {
+            write!(out, "\x1b_Gf=100,a=T,c={},r={},m={};", width, height, more)?;
+        } else {
+            write!(out, "\x1b_Gm={};", more)?;
+        }
+        out.write_all(chunk)?;
+        write!(out, "\x1b\\")?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+fn print_iterm(img: &DynamicImage, width: u32, height: u32) -> anyhow::Result<()> {
+    let png = png_bytes(img)?;
+    let mut out = stdout().lock();
+    write!(
+        out,
+        "  \x1b]1337;File=inline=1;size={};width={};height={};preserveAspectRatio=1:{}\x07",
+        png.len(),
+        width,
+        height,
+        STANDARD.encode(&png)
+    )?;
+    out.flush()?;
+    Ok(())
+}
+
+fn print_sixel(img: &DynamicImage, width: u32, height: u32) -> anyhow::Result<()> {
+    let (cell_width, cell_height) = match crossterm::terminal::window_size() {
+        Ok(size) if size.width > 0 && size.columns > 0 => (
+            size.width as u32 / size.columns as u32,
+            size.height as u32 / size.rows.max(1) as u32,
+        ),
+        _ => (8, 16),
+    };
+    let img = img
+        .resize(
+            width * cell_width,
+            height * cell_height,
+            FilterType::Triangle,
+        )
+        .to_rgb8();
+    let (img_width, img_height) = img.dimensions();
+
+    let quantize = |v: u8| (v as u32 * 5 + 127) / 255;
+    let mut out = String::from("\x1bPq");
+    out.push_str(&format!("\"1;1;{};{}", img_width, img_height));
+    for i in 0..216 {
+        let (r, g, b) = (i / 36, (i / 6) % 6, i % 6);
+        out.push_str(&format!("#{};2;{};{};{}", i, r * 20, g * 20, b * 20));
+    }
+
+    for band in (0..img_height).step_by(6) {
+        let mut colors = vec![vec![0u8; img_width as usize]; 216];
+        let mut used = vec![false; 216];
+        for dy in 0..6.min(img_height - band) {
+            for x in 0..img_width {
+                let p = img.get_pixel(x, band + dy);
+                let index = (quantize(p[0]) * 36 + quantize(p[1]) * 6 + quantize(p[2])) as usize;
+                colors[index][x as usize] |= 1 << dy;
+                used[index] = true;
+            }
+        }
+
+        for (index, sixels) in colors.iter().enumerate().filter(|(i, _)| used[*i]) {
+            out.push_str(&format!("#{}", index));
+            let mut x = 0;
+            while x < sixels.len() {
+                let value = sixels[x];
+                let mut run = 1;
+                while x + run < sixels.len() && sixels[x + run] == value {
+                    run += 1;
+                }
+                let ch = (63 + value) as char;
+                if run > 3 {
+                    out.push_str(&format!("!{}{}", run, ch));
+                } else {
+                    (0..run).for_each(|_| out.push(ch));
+                }
+                x += run;
+            }
+            out.push('$');
+        }
+        out.push('-');
+    }
+    out.push_str("\x1b\\");
+
+    let mut stdout = stdout().lock();
+    write!(stdout, "  {}", out)?;
+    stdout.flush()?;
+    Ok(())
+}
+
+pub async fn fetch_image(url: &str) -> Option<DynamicImage> {
+    let mut hasher = DefaultHasher::new();
+    url.hash(&mut hasher);
+    let path = config::cache_dir()
+        .join("posters")
+        .join(format!("{:x}", hasher.finish()));
+
+    if let Ok(bytes) = fs::read(&path) {
+        if let Ok(img) = image::load_from_memory(&bytes) {
+            return Some(img);
+        }
+    }
+
+    let resp = config::http_client().get(url).send().await.ok()?;
+    let bytes = resp.bytes().await.ok()?;
+    let img = image::load_from_memory(&bytes).ok()?;
+
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let _ = fs::write(path, &bytes);
+    Some(img)
+}
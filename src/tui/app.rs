@@ -0,0 +1,644 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use animeku_cli::{
+    ext::{Ext, SOURCES},
+    models::{Episode, Meta, Movie, Stream},
+    pipeline::Pipeline,
+    t,
+};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use image::DynamicImage;
+use ratatui::{layout::Rect, widgets::ListState};
+use tokio::sync::{mpsc::UnboundedSender, Semaphore};
+
+use crate::{
+    config, metadata,
+    thumbnail::fetch_image,
+    watchlist::{Entry, Watchlist},
+};
+
+pub type SharedExt = Arc<dyn Ext>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Focus {
+    Search,
+    Results,
+    Episodes,
+    Streams,
+}
+
+impl Focus {
+    fn next(self) -> Self {
+        match self {
+            Focus::Search => Focus::Results,
+            Focus::Results => Focus::Episodes,
+            Focus::Episodes => Focus::Streams,
+            Focus::Streams => Focus::Search,
+        }
+    }
+
+    fn prev(self) -> Self {
+        match self {
+            Focus::Search => Focus::Streams,
+            Focus::Results => Focus::Search,
+            Focus::Episodes => Focus::Results,
+            Focus::Streams => Focus::Episodes,
+        }
+    }
+}
+
+pub enum Msg {
+    Movies {
+        request: u64,
+        page: usize,
+        movies: Vec<Movie>,
+        total: u64,
+    },
+    Episodes {
+        request: u64,
+        source: usize,
+        movie_id: String,
+        episodes: Vec<Episode>,
+        meta: Meta,
+    },
+    Poster {
+        url: String,
+        image: DynamicImage,
+    },
+    Streams {
+        request: u64,
+        source: usize,
+        episode_id: String,
+        streams: Vec<Stream>,
+    },
+    /// A warning that doesn't end the current request.
+    Notice(String),
+    PrefetchFailed {
+        source: usize,
+        movie_id: String,
+    },
+    Error {
+        request: u64,
+        message: String,
+    },
+}
+
+pub enum Action {
+    None,
+    Quit,
+    Play(Stream),
+}
+
+#[derive(Default)]
+pub struct Areas {
+    pub search: Rect,
+    pub results: Rect,
+    pub episodes: Rect,
+    pub streams: Rect,
+}
+
+/// Metadata lookups that fail show up in the status line.
+fn pipeline(source: usize, tx: &UnboundedSender<Msg>) -> SharedExt {
+    let tx = tx.clone();
+    Arc::new(
+        Pipeline::for_source(source, &config::get().ext()).with_enricher(metadata::enricher(
+            SOURCES[source],
+            move |message| {
+                let _ = tx.send(Msg::Notice(message));
+            },
+        )),
+    )
+}
+
+pub struct App {
+    pub source: usize,
+    pub ext: SharedExt,
+    pub query: String,
+    pub focus: Focus,
+    pub page: usize,
+    pub total: u64,
+    pub movies: Vec<Movie>,
+    pub movie_state: ListState,
+    pub episodes: Vec<Episode>,
+    pub episode_state: ListState,
+    pub meta: Option<Meta>,
+    pub streams: Vec<Stream>,
+    pub stream_state: ListState,
+    pub posters: HashMap<String, DynamicImage>,
+    pending_posters: HashSet<String>,
+    pending_episodes: HashSet<String>,
+    poster_permits: Arc<Semaphore>,
+    pub status: String,
+    pub loading: bool,
+    pub areas: Areas,
+    /// Keyed by source and id, ids of different sources can collide.
+    episode_cache: HashMap<(usize, String), (Vec<Episode>, Meta)>,
+    stream_cache: HashMap<(usize, String), Vec<Stream>>,
+    request: u64,
+    tx: UnboundedSender<Msg>,
+}
+
+impl App {
+    pub fn new(source: usize, query: String, tx: UnboundedSender<Msg>) -> Self {
+        Self {
+            source,
+            ext: pipeline(source, &tx),
+            query,
+            focus: Focus::Search,
+            page: 1,
+            total: 0,
+            movies: Vec::new(),
+            movie_state: ListState::default(),
+            episodes: Vec::new(),
+            episode_state: ListState::default(),
+            meta: None,
+            streams: Vec::new(),
+            stream_state: ListState::default(),
+            posters: HashMap::new(),
+            pending_posters: HashSet::new(),
+            pending_episodes: HashSet::new(),
+            poster_permits: Arc::new(Semaphore::new(4)),
+            status: t!("tui.help").into(),
+            loading: false,
+            areas: Areas::default(),
+            episode_cache: HashMap::new(),
+            stream_cache: HashMap::new(),
+            request: 0,
+            tx,
+        }
+    }
+
+    pub fn source_label(&self) -> &'static str {
+        match self.source {
+            0 => t!("input.source.anime"),
+            1 => t!("input.source.movie"),
+            _ => t!("input.source.tenflix"),
+        }
+    }
+
+    pub fn details(&self) -> Option<&Meta> {
+        if self.focus == Focus::Results {
+            let movie = self.movies.get(self.movie_state.selected()?)?;
+            if let Some((_, meta)) = self.episode_cache.get(&(self.source, movie.id.clone())) {
+                return Some(meta);
+            }
+            return Some(&movie.meta);
+        }
+        self.meta.as_ref()
+    }
+
+    pub fn poster(&self) -> Option<&DynamicImage> {
+        let url = self.details()?.thumb_url.as_ref()?;
+        self.posters.get(url)
+    }
+
+    fn next_request(&mut self) -> u64 {
+        self.request += 1;
+        self.loading = true;
+        self.request
+    }
+
+    pub fn search(&mut self) {
+        if self.query.trim().is_empty() {
+            return;
+        }
+        let request = self.next_request();
+        let ext = self.ext.clone();
+        let tx = self.tx.clone();
+        let query = self.query.trim().to_string();
+        let page = self.page;
+        self.status = t!("search.progress", query);
+
+        tokio::spawn(async move {
+            let result = ext.search(query, page).await;
+            let _ = tx.send(match result {
+                Ok((movies, total)) => Msg::Movies {
+                    request,
+                    page,
+                    movies,
+                    total,
+                },
+                Err(e) => Msg::Error {
+                    request,
+                    message: format!("{:#}", e),
+                },
+            });
+        });
+    }
+
+    fn load_episodes(&mut self, movie: Movie) {
+        let key = (self.source, movie.id.clone());
+        if let Some((episodes, meta)) = self.episode_cache.get(&key).cloned() {
+            self.set_episodes(episodes, meta);
+            return;
+        }
+
+        let request = self.next_request();
+        let source = self.source;
+        let ext = self.ext.clone();
+        let tx = self.tx.clone();
+        self.status = t!("episode.progress", movie.title);
+
+        tokio::spawn(async move {
+            let movie_id = movie.id.clone();
+            let result = ext.get_episodes(movie).await;
+            let _ = tx.send(match result {
+                Ok((episodes, meta)) => Msg::Episodes {
+                    request,
+                    source,
+                    movie_id,
+                    episodes,
+                    meta,
+                },
+                Err(e) => Msg::Error {
+                    request,
+                    message: format!("{:#}", e),
+                },
+            });
+        });
+    }
+
+    fn prefetch_episodes(&mut self) {
+        let Some(movie) = self.movie_state.selected().and_then(|i| self.movies.get(i)) else {
+            return;
+        };
+        if self
+            .episode_cache
+            .contains_key(&(self.source, movie.id.clone()))
+            || !self.pending_episodes.insert(movie.id.clone())
+        {
+            return;
+        }
+
+        let source = self.source;
+        let ext = self.ext.clone();
+        let tx = self.tx.clone();
+        let movie = movie.clone();
+        tokio::spawn(async move {
+            let movie_id = movie.id.clone();
+            let _ = tx.send(match ext.get_episodes(movie).await {
+                Ok((episodes, meta)) => Msg::Episodes {
+                    request: 0,
+                    source,
+                    movie_id,
+                    episodes,
+                    meta,
+                },
+                Err(_) => Msg::PrefetchFailed { source, movie_id },
+            });
+        });
+    }
+
+    fn load_streams(&mut self, episode: Episode) {
+        if let Some(streams) = self
+            .stream_cache
+            .get(&(self.source, episode.id.clone()))
+            .cloned()
+        {
+            self.set_streams(streams);
+            return;
+        }
+
+        let request = self.next_request();
+        let source = self.source;
+        let ext = self.ext.clone();
+        let tx = self.tx.clone();
+        self.status = t!("stream.progress", episode.title);
+
+        tokio::spawn(async move {
+            let episode_id = episode.id.clone();
+            let result = ext.get_stream_urls(episode).await;
+            let _ = tx.send(match result {
+                Ok(streams) => Msg::Streams {
+                    request,
+                    source,
+                    episode_id,
+                    streams,
+                },
+                Err(e) => Msg::Error {
+                    request,
+                    message: format!("{:#}", e),
+                },
+            });
+        });
+    }
+
+    fn load_poster(&mut self, url: String) {
+        if self.posters.contains_key(&url) || !self.pending_posters.insert(url.clone()) {
+            return;
+        }
+        let tx = self.tx.clone();
+        let permits = self.poster_permits.clone();
+        tokio::spawn(async move {
+            let _permit = permits.acquire().await;
+            if let Some(image) = fetch_image(&url).await {
+                let _ = tx.send(Msg::Poster { url, image });
+            }
+        });
+    }
+
+    fn set_episodes(&mut self, episodes: Vec<Episode>, meta: Meta) {
+        self.episodes = episodes;
+        self.episode_state
+            .select((!self.episodes.is_empty()).then_some(0));
+        self.meta = Some(meta);
+        self.streams.clear();
+        self.stream_state.select(None);
+        self.focus = Focus::Episodes;
+    }
+
+    fn set_streams(&mut self, streams: Vec<Stream>) {
+        self.streams = streams;
+        self.stream_state
+            .select((!self.streams.is_empty()).then_some(0));
+        self.focus = Focus::Streams;
+    }
+
+    pub fn handle_msg(&mut self, msg: Msg) {
+        match msg {
+            Msg::Movies {
+                request,
+                page,
+                movies,
+                total,
+            } if request == self.request => {
+                self.loading = false;
+                if movies.is_empty() && page > 1 {
+                    self.page = page - 1;
+                    self.status = t!("tui.last_page").into();
+                    return;
+                }
+                self.status = if movies.is_empty() {
+                    t!("search.not_found").into()
+                } else {
+                    t!("search.found", total)
+                };
+                for url in movies.iter().filter_map(|m| m.meta.thumb_url.clone()) {
+                    self.load_poster(url);
+                }
+                self.page = page;
+                self.total = total;
+                self.movies = movies;
+                self.movie_state
+                    .select((!self.movies.is_empty()).then_some(0));
+                self.focus = Focus::Results;
+                self.prefetch_episodes();
+            }
+            Msg::Episodes {
+                request,
+                source,
+                movie_id,
+                episodes,
+                meta,
+            } if source == self.source => {
+                self.pending_episodes.remove(&movie_id);
+                if let Some(ref url) = meta.thumb_url {
+                    self.load_poster(url.clone());
+                }
+                self.episode_cache
+                    .insert((source, movie_id), (episodes.clone(), meta.clone()));
+                if request == self.request {
+                    self.loading = false;
+                    self.status = t!("status.success").into();
+                    self.set_episodes(episodes, meta);
+                }
+            }
+            Msg::Streams {
+                request,
+                source,
+                episode_id,
+                streams,
+            } if source == self.source => {
+                self.stream_cache
+                    .insert((source, episode_id), streams.clone());
+                if request == self.request {
+                    self.loading = false;
+                    self.status = t!("status.success").into();
+                    self.set_streams(streams);
+                }
+            }
+            Msg::Poster { url, image } => {
+                self.pending_posters.remove(&url);
+                self.posters.insert(url, image);
+            }
+            Msg::PrefetchFailed { source, movie_id } if source == self.source => {
+                self.pending_episodes.remove(&movie_id);
+            }
+            Msg::Notice(message) => self.status = message,
+            Msg::Error { request, message } if request == self.request => {
+                self.loading = false;
+                self.status = message;
+            }
+            _ => {}
+        }
+    }
+
+    fn cycle_source(&mut self) {
+        self.source = (self.source + 1) % SOURCES.len();
+        self.ext = pipeline(self.source, &self.tx);
+        // Drops results still in flight for the previous source.
+        self.next_request();
+        self.loading = false;
+        self.episode_cache.clear();
+        self.pending_episodes.clear();
+        self.stream_cache.clear();
+        self.movies.clear();
+        self.movie_state.select(None);
+        self.episodes.clear();
+        self.episode_state.select(None);
+        self.streams.clear();
+        self.stream_state.select(None);
+        self.meta = None;
+        self.page = 1;
+    }
+
+    fn list_len(&self, focus: Focus) -> usize {
+        match focus {
+            Focus::Search => 0,
+            Focus::Results => self.movies.len(),
+            Focus::Episodes => self.episodes.len(),
+            Focus::Streams => self.streams.len(),
+        }
+    }
+
+    fn list_state(&mut self, focus: Focus) -> Option<&mut ListState> {
+        match focus {
+            Focus::Search => None,
+            Focus::Results => Some(&mut self.movie_state),
+            Focus::Episodes => Some(&mut self.episode_state),
+            Focus::Streams => Some(&mut self.stream_state),
+        }
+    }
+
+    fn move_selection(&mut self, focus: Focus, delta: isize) {
+        let len = self.list_len(focus);
+        if len == 0 {
+            return;
+        }
+        if let Some(state) = self.list_state(focus) {
+            let current = state.selected().unwrap_or(0) as isize;
+            let next = (current + delta).clamp(0, len as isize - 1);
+            state.select(Some(next as usize));
+        }
+        if focus == Focus::Results {
+            self.prefetch_episodes();
+        }
+    }
+
+    fn activate(&mut self, focus: Focus) -> Action {
+        match focus {
+            Focus::Search => {
+                self.page = 1;
+                self.search();
+            }
+            Focus::Results => {
+                if let Some(movie) = self.movie_state.selected().and_then(|i| self.movies.get(i)) {
+                    self.load_episodes(movie.clone());
+                }
+            }
+            Focus::Episodes => {
+                if let Some(episode) = self
+                    .episode_state
+                    .selected()
+                    .and_then(|i| self.episodes.get(i))
+                {
+                    self.load_streams(episode.clone());
+                }
+            }
+            Focus::Streams => {
+                if let Some(stream) = self
+                    .stream_state
+                    .selected()
+                    .and_then(|i| self.streams.get(i))
+                {
+                    return Action::Play(stream.clone());
+                }
+            }
+        }
+        Action::None
+    }
+
+    /// Saves the highlighted result to the favorites, or removes it.
+    fn toggle_favorite(&mut self) {
+        let Some(movie) = self.movie_state.selected().and_then(|i| self.movies.get(i)) else {
+            return;
+        };
+        let source = SOURCES[self.source];
+        let mut watchlist = Watchlist::load();
+        let message = if watchlist.remove(source, &movie.id).is_some() {
+            t!("favorite.removed", movie.title.trim())
+        } else {
+            let cached = self.episode_cache.get(&(self.source, movie.id.clone()));
+            let episodes = cached.map_or(0, |(episodes, _)| episodes.len());
+            let mut entry = Entry::new(source, movie, episodes);
+            entry.thumb_url = cached
+                .and_then(|(_, meta)| meta.thumb_url.clone())
+                .or(entry.thumb_url);
+            watchlist.add(entry);
+            t!("favorite.added", movie.title.trim())
+        };
+        self.status = match watchlist.save() {
+            Ok(()) => message,
+            Err(e) => e.to_string(),
+        };
+    }
+
+    fn change_page(&mut self, delta: isize) {
+        let page = self.page as isize + delta;
+        if page < 1 || self.loading {
+            return;
+        }
+        self.page = page as usize;
+        self.search();
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> Action {
+        if key.modifiers.contains(KeyModifiers::CONTROL) {
+            match key.code {
+                KeyCode::Char('c') => return Action::Quit,
+                KeyCode::Char('s') => self.cycle_source(),
+                _ => {}
+            }
+            return Action::None;
+        }
+
+        match key.code {
+            KeyCode::Tab => self.focus = self.focus.next(),
+            KeyCode::BackTab => self.focus = self.focus.prev(),
+            KeyCode::F(2) => self.cycle_source(),
+            KeyCode::Enter => return self.activate(self.focus),
+            _ if self.focus == Focus::Search => match key.code {
+                KeyCode::Char(c) => self.query.push(c),
+                KeyCode::Backspace => {
+                    self.query.pop();
+                }
+                KeyCode::Esc | KeyCode::Down => self.focus = Focus::Results,
+                _ => {}
+            },
+            KeyCode::Char('q') | KeyCode::Esc => return Action::Quit,
+            KeyCode::Char('/') => self.focus = Focus::Search,
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(self.focus, -1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(self.focus, 1),
+            KeyCode::PageUp => self.move_selection(self.focus, -10),
+            KeyCode::PageDown => self.move_selection(self.focus, 10),
+            KeyCode::Home => self.move_selection(self.focus, isize::MIN / 2),
+            KeyCode::End => self.move_selection(self.focus, isize::MAX / 2),
+            KeyCode::Left | KeyCode::Char('h') => self.focus = self.focus.prev(),
+            KeyCode::Right | KeyCode::Char('l') => self.focus = self.focus.next(),
+            KeyCode::Char('n') if self.focus == Focus::Results => self.change_page(1),
+            KeyCode::Char('p') if self.focus == Focus::Results => self.change_page(-1),
+            KeyCode::Char('f') if self.focus == Focus::Results => self.toggle_favorite(),
+            _ => {}
+        }
+        Action::None
+    }
+
+    fn focus_at(&self, column: u16, row: u16) -> Option<(Focus, Rect)> {
+        let position = (column, row);
+        [
+            (Focus::Search, self.areas.search),
+            (Focus::Results, self.areas.results),
+            (Focus::Episodes, self.areas.episodes),
+            (Focus::Streams, self.areas.streams),
+        ]
+        .into_iter()
+        .find(|(_, rect)| contains(rect, position))
+    }
+
+    pub fn handle_mouse(&mut self, mouse: MouseEvent) -> Action {
+        let Some((focus, rect)) = self.focus_at(mouse.column, mouse.row) else {
+            return Action::None;
+        };
+
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                self.focus = focus;
+                let len = self.list_len(focus);
+                if let Some(state) = self.list_state(focus) {
+                    let row = mouse.row.saturating_sub(rect.y + 1) as usize + state.offset();
+                    if row < len {
+                        let already_selected = state.selected() == Some(row);
+                        state.select(Some(row));
+                        if already_selected {
+                            return self.activate(focus);
+                        }
+                        if focus == Focus::Results {
+                            self.prefetch_episodes();
+                        }
+                    }
+                }
+            }
+            MouseEventKind::ScrollUp => self.move_selection(focus, -1),
+            MouseEventKind::ScrollDown => self.move_selection(focus, 1),
+            _ => {}
+        }
+        Action::None
+    }
+}
+
+fn contains(rect: &Rect, (column, row): (u16, u16)) -> bool {
+    column >= rect.x && column < rect.x + rect.width && row >= rect.y && row < rect.y + rect.height
+}
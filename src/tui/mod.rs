@@ -0,0 +1,107 @@
+use std::{
+    io::{stdout, Stdout},
+    process::Command,
+    time::Duration,
+};
+
+use animeku_cli::{models::Stream, t};
+use crossterm::{
+    event::{self, DisableMouseCapture, EnableMouseCapture, Event},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{backend::CrosstermBackend, Terminal};
+use tokio::sync::mpsc;
+
+use self::app::{Action, App};
+
+use crate::config;
+
+mod app;
+mod poster;
+mod ui;
+
+type Term = Terminal<CrosstermBackend<Stdout>>;
+
+fn setup() -> anyhow::Result<Term> {
+    enable_raw_mode()?;
+    execute!(stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+    Ok(Terminal::new(CrosstermBackend::new(stdout()))?)
+}
+
+fn restore() {
+    let _ = disable_raw_mode();
+    let _ = execute!(stdout(), LeaveAlternateScreen, DisableMouseCapture);
+}
+
+pub async fn run(source: usize, query: Option<String>) -> anyhow::Result<()> {
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        restore();
+        hook(info);
+    }));
+
+    let mut terminal = setup()?;
+    let result = event_loop(&mut terminal, source, query).await;
+    restore();
+    result
+}
+
+async fn event_loop(
+    terminal: &mut Term,
+    source: usize,
+    query: Option<String>,
+) -> anyhow::Result<()> {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut app = App::new(source, query.clone().unwrap_or_default(), tx);
+    if query.is_some() {
+        app.search();
+    }
+
+    loop {
+        terminal.draw(|frame| ui::draw(frame, &mut app))?;
+
+        while let Ok(msg) = rx.try_recv() {
+            app.handle_msg(msg);
+        }
+
+        if !tokio::task::block_in_place(|| event::poll(Duration::from_millis(50)))? {
+            continue;
+        }
+        let action = match event::read()? {
+            Event::Key(key) if key.kind == event::KeyEventKind::Press => app.handle_key(key),
+            Event::Mouse(mouse) => app.handle_mouse(mouse),
+            _ => Action::None,
+        };
+
+        match action {
+            Action::Quit => break,
+            Action::Play(mut stream) => {
+                if config::get().player.restream {
+                    stream.url = crate::server::proxy::local_url(&stream).await?;
+                }
+                app.status = play(terminal, stream)?;
+            }
+            Action::None => {}
+        }
+    }
+    Ok(())
+}
+
+fn play(terminal: &mut Term, stream: Stream) -> anyhow::Result<String> {
+    let player = &config::get().player;
+
+    restore();
+    let status = Command::new(&player.command)
+        .args(&player.args)
+        .arg(&stream.url)
+        .status();
+    enable_raw_mode()?;
+    execute!(stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+    terminal.clear()?;
+
+    Ok(match status {
+        Ok(status) if status.success() => stream.title,
+        _ => t!("player.failed", player.command),
+    })
+}
@@ -0,0 +1,64 @@
+use image::{imageops::FilterType, DynamicImage, GenericImageView};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    widgets::Widget,
+};
+
+use crate::{config::ThumbnailMode, thumbnail};
+
+pub struct Poster<'a> {
+    image: &'a DynamicImage,
+}
+
+impl<'a> Poster<'a> {
+    pub fn new(image: &'a DynamicImage) -> Self {
+        Self { image }
+    }
+}
+
+impl Widget for Poster<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.width == 0 || area.height == 0 {
+            return;
+        }
+
+        match thumbnail::mode() {
+            ThumbnailMode::Off => return,
+            ThumbnailMode::Ascii => {
+                let lines =
+                    thumbnail::ascii_lines(self.image, area.width as u32, area.height as u32);
+                for (y, line) in lines.iter().enumerate() {
+                    buf.set_string(area.x, area.y + y as u16, line, Style::default());
+                }
+                return;
+            }
+            _ => {}
+        }
+
+        let resized = self.image.resize(
+            area.width as u32,
+            area.height as u32 * 2,
+            FilterType::Triangle,
+        );
+        let (width, height) = resized.dimensions();
+        let offset_x = area.x + (area.width - width as u16) / 2;
+
+        for y in (0..height).step_by(2) {
+            for x in 0..width {
+                let top = resized.get_pixel(x, y);
+                let bottom = if y + 1 < height {
+                    resized.get_pixel(x, y + 1)
+                } else {
+                    top
+                };
+
+                let cell = buf.get_mut(offset_x + x as u16, area.y + (y / 2) as u16);
+                cell.set_char('▀')
+                    .set_fg(Color::Rgb(top[0], top[1], top[2]))
+                    .set_bg(Color::Rgb(bottom[0], bottom[1], bottom[2]));
+            }
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use animeku_cli::{i18n::meta_key, t};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    Frame,
+};
+
+use super::{
+    app::{App, Focus},
+    poster::Poster,
+};
+fn block(title: String, focused: bool) -> Block<'static> {
+    let style = if focused {
+        Style::default().fg(Color::Green)
+    } else {
+        Style::default()
+    };
+    Block::default()
+        .borders(Borders::ALL)
+        .border_style(style)
+        .title(title)
+}
+
+fn render_list<T: std::fmt::Display>(
+    frame: &mut Frame,
+    area: Rect,
+    title: String,
+    items: &[T],
+    state: &mut ListState,
+    focused: bool,
+) {
+    let items = items
+        .iter()
+        .map(|item| ListItem::new(item.to_string()))
+        .collect::<Vec<_>>();
+    let list = List::new(items)
+        .block(block(title, focused))
+        .highlight_style(
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Rgb(252, 136, 3))
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("› ");
+    frame.render_stateful_widget(list, area, state);
+}
+
+pub fn draw(frame: &mut Frame, app: &mut App) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .split(frame.size());
+
+    let search = Paragraph::new(format!("{}▏", app.query)).block(block(
+        format!(" {} · {} ", t!("input.title"), app.source_label()),
+        app.focus == Focus::Search,
+    ));
+    frame.render_widget(search, rows[0]);
+    app.areas.search = rows[0];
+
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(30),
+            Constraint::Percentage(30),
+            Constraint::Percentage(40),
+        ])
+        .split(rows[1]);
+
+    let results_title = format!(" {} · {} ", t!("tui.results"), t!("tui.page", app.page));
+    render_list(
+        frame,
+        columns[0],
+        results_title,
+        &app.movies,
+        &mut app.movie_state,
+        app.focus == Focus::Results,
+    );
+    app.areas.results = columns[0];
+
+    render_list(
+        frame,
+        columns[1],
+        format!(" {} ", t!("tui.episodes")),
+        &app.episodes,
+        &mut app.episode_state,
+        app.focus == Focus::Episodes,
+    );
+    app.areas.episodes = columns[1];
+
+    let right = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(65), Constraint::Percentage(35)])
+        .split(columns[2]);
+
+    draw_details(frame, right[0], app);
+
+    render_list(
+        frame,
+        right[1],
+        format!(" {} ", t!("tui.streams")),
+        &app.streams,
+        &mut app.stream_state,
+        app.focus == Focus::Streams,
+    );
+    app.areas.streams = right[1];
+
+    let status = if app.loading {
+        format!("⏳ {}", app.status)
+    } else {
+        app.status.clone()
+    };
+    frame.render_widget(
+        Paragraph::new(status).style(Style::default().fg(Color::Yellow)),
+        rows[2],
+    );
+}
+
+fn draw_details(frame: &mut Frame, area: Rect, app: &App) {
+    let details = block(t!("details.title").into(), false);
+    let inner = details.inner(area);
+    frame.render_widget(details, area);
+
+    let Some(meta) = app.details() else {
+        return;
+    };
+
+    let parts = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
+        .split(inner);
+
+    if let Some(image) = app.poster() {
+        frame.render_widget(Poster::new(image), parts[0]);
+    }
+
+    let lines = meta
+        .data
+        .iter()
+        .map(|(k, v)| {
+            Line::from(vec![
+                Span::styled(
+                    meta_key(k),
+                    Style::default()
+                        .fg(Color::White)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(" : "),
+                Span::raw(v.clone()),
+            ])
+        })
+        .collect::<Vec<_>>();
+    frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: true }), parts[1]);
+}
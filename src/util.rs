@@ -1,6 +1,7 @@
-use colored::Colorize;
 use humansize::{format_size, BINARY};
-use reqwest::{header::CONTENT_LENGTH, Client};
+use reqwest::{header::CONTENT_LENGTH, Client, RequestBuilder, Url};
+
+use crate::models::Stream;
 
 #[macro_export]
 macro_rules! regex {
@@ -10,6 +11,19 @@ macro_rules! regex {
     }};
 }
 
+pub fn clean_title(title: &str) -> String {
+    regex!(r"(?i)\s*(\(.*?\)|\[.*?\]|subtitle indonesia|sub indo|batch)")
+        .replace_all(title, "")
+        .trim()
+        .to_string()
+}
+
+pub fn episode_number(title: &str) -> Option<usize> {
+    regex!(r"(?i)\b(?:episode|eps?)\.?\s*(\d+)")
+        .captures(title)
+        .and_then(|cap| cap[1].parse().ok())
+}
+
 pub async fn get_filesize(client: &Client, url: &str) -> Option<String> {
     if url.contains("nontonanime") {
         return None;
@@ -47,38 +61,19 @@ pub async fn get_real_url(client: &Client, url: String) -> anyhow::Result<String
     Ok(url)
 }
 
-pub async fn show_image_thumb(url: String) {
-    let client = Client::new();
-    if let Ok(resp) = client.get(url).send().await {
-        if let Ok(bytes) = resp.bytes().await {
-            if let Ok(img) = image::load_from_memory(&bytes) {
-                let conf = viuer::Config {
-                    transparent: true,
-                    width: Some(50),
-                    height: Some(30),
-                    y: 8,
-                    x: 2,
-                    ..Default::default()
-                };
-                if viuer::print(&img, &conf).is_ok() {
-                    println!();
-                }
-            }
-        }
-    }
-}
+pub fn stream_request(client: &Client, stream: &Stream) -> anyhow::Result<RequestBuilder> {
+    let mut url = Url::parse(&stream.url)?;
+    let username = url.username().to_string();
+    let password = url.password().map(str::to_string);
+    let _ = url.set_username("");
+    let _ = url.set_password(None);
 
-pub fn clearscreen_and_show_banner() -> anyhow::Result<()> {
-    clearscreen::clear()?;
-
-    eprintln!(
-        "{} v{} {} val \n",
-        r#"
-  ▄▀█ █▄░█ █ █▀▄▀█ █▀▀ █▄▀ █░█ ▄▄ █▀▀ █░░ █
-  █▀█ █░▀█ █ █░▀░█ ██▄ █░█ █▄█ ░░ █▄▄ █▄▄ █ "#
-            .bright_green(),
-        env!("CARGO_PKG_VERSION"),
-        "©".cyan()
-    );
-    Ok(())
+    let mut req = client.get(url);
+    if !username.is_empty() {
+        req = req.basic_auth(username, password);
+    }
+    for (key, value) in &stream.headers {
+        req = req.header(key, value);
+    }
+    Ok(req)
 }
@@ -0,0 +1,397 @@
+use std::{
+    cmp::Reverse,
+    fs,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use animeku_cli::models::{Meta, Movie};
+use serde::{Deserialize, Serialize};
+
+use crate::{config, history::History};
+
+pub static DEFAULT_TAGS: [&str; 4] = ["plan to watch", "watching", "completed", "dropped"];
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Entry {
+    pub source: String,
+    pub id: String,
+    pub title: String,
+    pub thumb_url: Option<String>,
+    pub episodes: usize,
+    pub progress: usize,
+    pub tags: Vec<String>,
+    pub followed: bool,
+    pub seen_ids: Vec<String>,
+    pub releases: Vec<u64>,
+    pub ongoing: bool,
+    pub mal_id: Option<u64>,
+    pub added: u64,
+    pub updated: u64,
+    pub checked: u64,
+    pub modified: u64,
+}
+
+impl Entry {
+    /// Compares what syncs, the episode count and `updated` are only
+    /// bookkeeping of the last visit.
+    fn same(&self, other: &Entry) -> bool {
+        let strip = |e: &Entry| Entry {
+            episodes: 0,
+            updated: 0,
+            checked: 0,
+            modified: 0,
+            ..e.clone()
+        };
+        strip(self) == strip(other)
+    }
+
+    pub fn new(source: &str, movie: &Movie, episodes: usize) -> Self {
+        let now = now();
+        Self {
+            source: source.into(),
+            id: movie.id.clone(),
+            title: movie.title.trim().into(),
+            thumb_url: movie.meta.thumb_url.clone(),
+            episodes,
+            progress: 0,
+            tags: Vec::new(),
+            followed: false,
+            seen_ids: Vec::new(),
+            releases: Vec::new(),
+            ongoing: ongoing(&movie.meta),
+            mal_id: None,
+            added: now,
+            updated: now,
+            checked: 0,
+            modified: 0,
+        }
+    }
+
+    /// Ongoing nontonanime series, the only entries with a weekly schedule.
+    pub fn scheduled(&self) -> bool {
+        self.source == "anime" && self.ongoing
+    }
+
+    pub fn movie(&self) -> Movie {
+        let mut movie = Movie {
+            id: self.id.clone(),
+            title: self.title.clone(),
+            ..Default::default()
+        };
+        movie.meta.thumb_url = self.thumb_url.clone();
+        movie
+    }
+}
+
+pub fn ongoing(meta: &Meta) -> bool {
+    meta.data
+        .iter()
+        .any(|(key, value)| key == "On-Going" && value == "true")
+}
+
+impl std::fmt::Display for Entry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}] {} (", self.source, self.title)?;
+        if self.progress > 0 {
+            write!(f, "{}/", self.progress)?;
+        }
+        write!(f, "{} eps)", self.episodes)?;
+        if self.followed {
+            write!(f, " 🔔")?;
+        }
+        if !self.tags.is_empty() {
+            write!(f, " #{}", self.tags.join(" #"))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortBy {
+    #[default]
+    Added,
+    Updated,
+    Title,
+    Source,
+}
+
+impl SortBy {
+    pub fn next(self) -> Self {
+        match self {
+            SortBy::Added => SortBy::Updated,
+            SortBy::Updated => SortBy::Title,
+            SortBy::Title => SortBy::Source,
+            SortBy::Source => SortBy::Added,
+        }
+    }
+}
+
+/// Marks an entry removed at `modified`, so other devices drop it on sync.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Removed {
+    pub source: String,
+    pub id: String,
+    pub modified: u64,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Watchlist {
+    pub sort: SortBy,
+    pub entries: Vec<Entry>,
+    pub removed: Vec<Removed>,
+}
+
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+pub fn path() -> PathBuf {
+    config::data_dir().join("watchlist.json")
+}
+
+impl Watchlist {
+    pub fn load() -> Self {
+        fs::read(path())
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    /// Saves the watchlist, stamping `modified` on every entry that changed
+    /// since the last save.
+    pub fn save(&self) -> anyhow::Result<()> {
+        let previous = Self::load();
+        let mut watchlist = self.clone();
+        for entry in watchlist.entries.iter_mut() {
+            match previous.get(&entry.source, &entry.id) {
+                Some(old) if !entry.same(old) && entry.modified <= old.modified => {
+                    entry.modified = now()
+                }
+                None if entry.modified == 0 => entry.modified = now(),
+                _ => {}
+            }
+        }
+
+        let path = path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_vec_pretty(&watchlist)?)?;
+        Ok(())
+    }
+
+    /// Last-writer-wins merge per entry, removals included.
+    pub fn merge(&mut self, other: Watchlist) {
+        for removed in other.removed {
+            match self
+                .removed
+                .iter_mut()
+                .find(|r| r.source == removed.source && r.id == removed.id)
+            {
+                Some(mine) => mine.modified = mine.modified.max(removed.modified),
+                None => self.removed.push(removed),
+            }
+        }
+        for entry in other.entries {
+            match self.get_mut(&entry.source, &entry.id) {
+                Some(mine) if entry.modified > mine.modified => *mine = entry,
+                Some(_) => {}
+                None => self.entries.push(entry),
+            }
+        }
+
+        let removed = self.removed.clone();
+        self.entries.retain(|e| {
+            !removed
+                .iter()
+                .any(|r| r.source == e.source && r.id == e.id && r.modified >= e.modified)
+        });
+        let entries = self.entries.clone();
+        self.removed
+            .retain(|r| !entries.iter().any(|e| r.source == e.source && r.id == e.id));
+    }
+
+    pub fn get(&self, source: &str, id: &str) -> Option<&Entry> {
+        self.entries
+            .iter()
+            .find(|e| e.source == source && e.id == id)
+    }
+
+    pub fn get_mut(&mut self, source: &str, id: &str) -> Option<&mut Entry> {
+        self.entries
+            .iter_mut()
+            .find(|e| e.source == source && e.id == id)
+    }
+
+    pub fn add(&mut self, mut entry: Entry) {
+        match self.get_mut(&entry.source, &entry.id) {
+            Some(existing) => {
+                existing.title = entry.title;
+                existing.thumb_url = entry.thumb_url.or(existing.thumb_url.take());
+                existing.episodes = entry.episodes;
+                existing.updated = now();
+            }
+            None => {
+                let watched = History::load().progress(&entry.source, &entry.id);
+                entry.progress = entry.progress.max(watched);
+                self.removed
+                    .retain(|r| r.source != entry.source || r.id != entry.id);
+                self.entries.push(entry);
+            }
+        }
+    }
+
+    pub fn remove(&mut self, source: &str, id: &str) -> Option<Entry> {
+        let index = self
+            .entries
+            .iter()
+            .position(|e| e.source == source && e.id == id)?;
+        self.removed.push(Removed {
+            source: source.into(),
+            id: id.into(),
+            modified: now(),
+        });
+        Some(self.entries.remove(index))
+    }
+
+    /// Follows `entry` with `seen_ids` as the episodes already known, an
+    /// empty list lets the next check take the current episodes instead.
+    pub fn follow(&mut self, entry: Entry, seen_ids: Vec<String>) {
+        let (source, id) = (entry.source.clone(), entry.id.clone());
+        if self.get(&source, &id).is_none() {
+            self.add(entry);
+        }
+        if let Some(entry) = self.get_mut(&source, &id) {
+            entry.followed = true;
+            entry.seen_ids = seen_ids;
+        }
+    }
+
+    /// Stops following, so a later follow starts from the episodes of that
+    /// moment instead of reporting everything released in between.
+    pub fn unfollow(&mut self, source: &str, id: &str) {
+        if let Some(entry) = self.get_mut(source, id) {
+            entry.followed = false;
+            entry.seen_ids.clear();
+        }
+    }
+
+    pub fn seen(&mut self, source: &str, id: &str, episodes: usize) -> bool {
+        match self.get_mut(source, id) {
+            Some(entry) if entry.episodes != episodes => {
+                entry.episodes = episodes;
+                entry.updated = now();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn sorted(&self) -> Vec<Entry> {
+        let mut entries = self.entries.clone();
+        match self.sort {
+            SortBy::Added => entries.sort_by_key(|e| Reverse(e.added)),
+            SortBy::Updated => entries.sort_by_key(|e| Reverse(e.updated)),
+            SortBy::Title => entries.sort_by_key(|e| e.title.to_lowercase()),
+            SortBy::Source => entries.sort_by_key(|e| (e.source.clone(), e.title.to_lowercase())),
+        }
+        entries
+    }
+
+    pub fn tags(&self) -> Vec<String> {
+        let mut tags = DEFAULT_TAGS.map(String::from).to_vec();
+        for tag in self.entries.iter().flat_map(|e| &e.tags) {
+            if !tags.contains(tag) {
+                tags.push(tag.clone());
+            }
+        }
+        tags
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str, progress: usize, modified: u64) -> Entry {
+        Entry {
+            source: "anime".into(),
+            id: id.into(),
+            title: id.to_uppercase(),
+            progress,
+            modified,
+            ..Default::default()
+        }
+    }
+
+    fn removed(id: &str, modified: u64) -> Removed {
+        Removed {
+            source: "anime".into(),
+            id: id.into(),
+            modified,
+        }
+    }
+
+    fn watchlist(entries: Vec<Entry>, removed: Vec<Removed>) -> Watchlist {
+        Watchlist {
+            entries,
+            removed,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn merge_keeps_last_writer() {
+        let mut mine = watchlist(vec![entry("a", 1, 10), entry("b", 5, 30)], vec![]);
+        let theirs = watchlist(
+            vec![entry("a", 3, 20), entry("b", 2, 20), entry("c", 1, 5)],
+            vec![],
+        );
+        mine.merge(theirs);
+
+        assert_eq!(mine.get("anime", "a").unwrap().progress, 3);
+        assert_eq!(mine.get("anime", "b").unwrap().progress, 5);
+        assert_eq!(mine.get("anime", "c").unwrap().progress, 1);
+        assert_eq!(mine.entries.len(), 3);
+    }
+
+    #[test]
+    fn merge_applies_tombstones() {
+        let mut mine = watchlist(vec![entry("a", 1, 10), entry("b", 1, 40)], vec![]);
+        let theirs = watchlist(vec![], vec![removed("a", 20), removed("b", 30)]);
+        mine.merge(theirs);
+
+        assert!(mine.get("anime", "a").is_none());
+        assert!(mine.get("anime", "b").is_some());
+        assert_eq!(mine.removed, vec![removed("a", 20)]);
+    }
+
+    #[test]
+    fn merge_readds_after_tombstone() {
+        let mut mine = watchlist(vec![], vec![removed("a", 10)]);
+        let theirs = watchlist(vec![entry("a", 2, 15)], vec![removed("a", 5)]);
+        mine.merge(theirs);
+
+        assert_eq!(mine.get("anime", "a").unwrap().progress, 2);
+        assert!(mine.removed.is_empty());
+    }
+
+    #[test]
+    fn seen_is_not_a_change() {
+        let old = entry("a", 1, 10);
+        let mut mine = watchlist(vec![old.clone()], vec![]);
+        assert!(mine.seen("anime", "a", 12));
+        assert!(mine.get("anime", "a").unwrap().same(&old));
+
+        mine.get_mut("anime", "a").unwrap().progress = 2;
+        assert!(!mine.get("anime", "a").unwrap().same(&old));
+    }
+}